@@ -8,7 +8,7 @@
 /// Metrics: Insert throughput, query throughput, memory usage, FPR validation
 use criterion::{Criterion, criterion_group, criterion_main};
 use pdatastructs::filters::Filter as PdataFilter;
-use sketches::benchmarks::Dataset;
+use sketches::benchmarks::{Dataset, bench_seed};
 use sketches::filters::bloom::BloomFilter;
 use sketches::filters::traits::ApproximateMembershipQuery;
 use sketches::hashing::AHasher;
@@ -19,7 +19,7 @@ use std::hint::black_box;
 // ============================================================================
 
 fn filter_insert_comparison(c: &mut Criterion) {
-    let dataset = Dataset::medium(42); // 100K items
+    let dataset = Dataset::medium(bench_seed()); // 100K items
     let n = dataset.inserted.len();
     let fpr = 0.01;
 
@@ -79,7 +79,7 @@ fn filter_insert_comparison(c: &mut Criterion) {
 // ============================================================================
 
 fn filter_query_comparison(c: &mut Criterion) {
-    let dataset = Dataset::medium(42);
+    let dataset = Dataset::medium(bench_seed());
     let n = dataset.inserted.len();
     let fpr = 0.01;
 
@@ -152,7 +152,7 @@ fn filter_query_comparison(c: &mut Criterion) {
 // ============================================================================
 
 fn filter_fpr_validation(c: &mut Criterion) {
-    let dataset = Dataset::large(42); // 1M items for better FPR measurement
+    let dataset = Dataset::large(bench_seed()); // 1M items for better FPR measurement
     let n = dataset.inserted.len();
     let fpr = 0.01;
 
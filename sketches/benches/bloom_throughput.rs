@@ -1,6 +1,6 @@
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use rand::Rng;
-use sketches::filters::bloom::BloomFilter;
+use sketches::filters::bloom::{BloomFilter, BulkBuilder};
 use sketches::filters::traits::ApproximateMembershipQuery;
 use sketches::hashing::AHasher;
 use std::hint::black_box;
@@ -41,5 +41,78 @@ fn bloom_query(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bloom_insertion, bloom_query);
+/// Very low false positive rates give large k, where position arithmetic dominates hashing.
+fn bloom_high_k_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bloom_high_k_query");
+
+    for fpr in [1e-2, 1e-6, 1e-12] {
+        let mut filter = BloomFilter::<_, AHasher>::new(100_000, fpr);
+        for i in 0..100_000u64 {
+            filter.insert(&i);
+        }
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(filter.num_hashes()),
+            &fpr,
+            |b, _| {
+                let mut i = 0u64;
+                b.iter(|| {
+                    // Present items, so every position is checked
+                    i = (i + 1) % 100_000;
+                    black_box(filter.contains(&i));
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Building a filter from scratch, item by item versus through a `BulkBuilder`.
+fn bloom_bulk_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bloom_bulk_build");
+    group.sample_size(10);
+    for (size, fpr) in [(1_000_000, 0.01), (10_000_000, 1e-4)] {
+        bulk_build_case(&mut group, size, fpr);
+    }
+    group.finish();
+}
+
+fn bulk_build_case(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    size: usize,
+    fpr: f64,
+) {
+    let items: Vec<u64> = {
+        let mut rng = rand::rng();
+        (0..size).map(|_| rng.random()).collect()
+    };
+
+    group.bench_function(BenchmarkId::new("sequential", size), |b| {
+        b.iter(|| {
+            let mut filter = BloomFilter::<_, AHasher>::new(size, fpr);
+            for item in &items {
+                filter.insert(black_box(item));
+            }
+            black_box(filter)
+        });
+    });
+
+    group.bench_function(BenchmarkId::new("bulk", size), |b| {
+        b.iter(|| {
+            let mut builder = BulkBuilder::<_, AHasher>::new(size, fpr);
+            for item in &items {
+                builder.add(black_box(item));
+            }
+            black_box(builder.finalize())
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    bloom_insertion,
+    bloom_query,
+    bloom_high_k_query,
+    bloom_bulk_build
+);
 criterion_main!(benches);
@@ -1,19 +1,22 @@
 /// Hasher Comparison Benchmarks
 ///
-/// Compares the three available hash function implementations with BloomFilter:
+/// Compares the available hash function implementations with BloomFilter:
 /// - AHasher (ahash)
 /// - XXHasher (xxhash3)
 /// - Murmur3Hasher (murmur3)
+/// - StdHasher (the standard library's SipHash, as a baseline)
 ///
 /// Metrics: Insert throughput, query throughput (positive/negative lookups)
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use sketches::benchmarks::Dataset;
+///
+/// Insert benchmarks draw their filters from a `BloomFilterPool`, so they measure insertion
+/// rather than allocating the bit array.
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use sketches::benchmarks::{BloomFilterPool, Dataset, bench_seed};
 use sketches::filters::bloom::BloomFilter;
 use sketches::filters::traits::ApproximateMembershipQuery;
-use sketches::hashing::{AHasher, Murmur3Hasher, XXHasher};
+use sketches::hashing::{AHasher, Murmur3Hasher, StdHasher, XXHasher};
 use std::hint::black_box;
 
-
 // ============================================================================
 // Insert Benchmarks - Compare hasher performance during insertions
 // ============================================================================
@@ -23,39 +26,54 @@ fn hasher_insert_comparison(c: &mut Criterion) {
 
     // Test with different dataset sizes
     for size in [1000, 10_000, 100_000] {
-        let dataset = Dataset::uniform(size, 42);
+        let dataset = Dataset::uniform(size, bench_seed());
         let fpr = 0.01;
 
         // AHasher
         group.bench_with_input(BenchmarkId::new("ahash", size), &dataset, |b, dataset| {
+            let mut pool = BloomFilterPool::<_, AHasher>::new();
             b.iter(|| {
-                let mut filter = BloomFilter::<_, AHasher>::new(dataset.inserted.len(), fpr);
+                let mut filter = pool.acquire(dataset.inserted.len(), fpr);
                 for item in &dataset.inserted {
                     filter.insert(black_box(item));
                 }
-                black_box(filter)
+                pool.release(black_box(filter));
             });
         });
 
         // XXHasher
         group.bench_with_input(BenchmarkId::new("xxhash3", size), &dataset, |b, dataset| {
+            let mut pool = BloomFilterPool::<_, XXHasher>::new();
             b.iter(|| {
-                let mut filter = BloomFilter::<_, XXHasher>::new(dataset.inserted.len(), fpr);
+                let mut filter = pool.acquire(dataset.inserted.len(), fpr);
                 for item in &dataset.inserted {
                     filter.insert(black_box(item));
                 }
-                black_box(filter)
+                pool.release(black_box(filter));
             });
         });
 
         // Murmur3Hasher
         group.bench_with_input(BenchmarkId::new("murmur3", size), &dataset, |b, dataset| {
+            let mut pool = BloomFilterPool::<_, Murmur3Hasher>::new();
+            b.iter(|| {
+                let mut filter = pool.acquire(dataset.inserted.len(), fpr);
+                for item in &dataset.inserted {
+                    filter.insert(black_box(item));
+                }
+                pool.release(black_box(filter));
+            });
+        });
+
+        // StdHasher
+        group.bench_with_input(BenchmarkId::new("std", size), &dataset, |b, dataset| {
+            let mut pool = BloomFilterPool::<_, StdHasher>::new();
             b.iter(|| {
-                let mut filter = BloomFilter::<_, Murmur3Hasher>::new(dataset.inserted.len(), fpr);
+                let mut filter = pool.acquire(dataset.inserted.len(), fpr);
                 for item in &dataset.inserted {
                     filter.insert(black_box(item));
                 }
-                black_box(filter)
+                pool.release(black_box(filter));
             });
         });
     }
@@ -72,18 +90,20 @@ fn hasher_query_comparison(c: &mut Criterion) {
 
     // Test with different dataset sizes
     for size in [1000, 10_000, 100_000] {
-        let dataset = Dataset::uniform(size, 42);
+        let dataset = Dataset::uniform(size, bench_seed());
         let fpr = 0.01;
 
         // Pre-build filters
         let mut filter_ahash = BloomFilter::<_, AHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_xxhash = BloomFilter::<_, XXHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_murmur3 = BloomFilter::<_, Murmur3Hasher>::new(dataset.inserted.len(), fpr);
+        let mut filter_std = BloomFilter::<_, StdHasher>::new(dataset.inserted.len(), fpr);
 
         for item in &dataset.inserted {
             filter_ahash.insert(item);
             filter_xxhash.insert(item);
             filter_murmur3.insert(item);
+            filter_std.insert(item);
         }
 
         // Combine positive and negative queries
@@ -131,6 +151,16 @@ fn hasher_query_comparison(c: &mut Criterion) {
                 });
             },
         );
+
+        // StdHasher queries
+        group.bench_with_input(BenchmarkId::new("std", size), &query_items, |b, items| {
+            let mut idx = 0;
+            b.iter(|| {
+                let item = &items[idx % items.len()];
+                idx += 1;
+                black_box(filter_std.contains(black_box(item)))
+            });
+        });
     }
 
     group.finish();
@@ -144,18 +174,20 @@ fn hasher_positive_query_comparison(c: &mut Criterion) {
     let mut group = c.benchmark_group("hasher_positive_queries");
 
     for size in [1000, 10_000, 100_000] {
-        let dataset = Dataset::uniform(size, 42);
+        let dataset = Dataset::uniform(size, bench_seed());
         let fpr = 0.01;
 
         // Pre-build filters
         let mut filter_ahash = BloomFilter::<_, AHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_xxhash = BloomFilter::<_, XXHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_murmur3 = BloomFilter::<_, Murmur3Hasher>::new(dataset.inserted.len(), fpr);
+        let mut filter_std = BloomFilter::<_, StdHasher>::new(dataset.inserted.len(), fpr);
 
         for item in &dataset.inserted {
             filter_ahash.insert(item);
             filter_xxhash.insert(item);
             filter_murmur3.insert(item);
+            filter_std.insert(item);
         }
 
         // Query only items we know are present
@@ -198,6 +230,16 @@ fn hasher_positive_query_comparison(c: &mut Criterion) {
                 });
             },
         );
+
+        // StdHasher
+        group.bench_with_input(BenchmarkId::new("std", size), query_items, |b, items| {
+            let mut idx = 0;
+            b.iter(|| {
+                let item = &items[idx % items.len()];
+                idx += 1;
+                black_box(filter_std.contains(black_box(item)))
+            });
+        });
     }
 
     group.finish();
@@ -211,18 +253,20 @@ fn hasher_negative_query_comparison(c: &mut Criterion) {
     let mut group = c.benchmark_group("hasher_negative_queries");
 
     for size in [1000, 10_000, 100_000] {
-        let dataset = Dataset::uniform(size, 42);
+        let dataset = Dataset::uniform(size, bench_seed());
         let fpr = 0.01;
 
         // Pre-build filters
         let mut filter_ahash = BloomFilter::<_, AHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_xxhash = BloomFilter::<_, XXHasher>::new(dataset.inserted.len(), fpr);
         let mut filter_murmur3 = BloomFilter::<_, Murmur3Hasher>::new(dataset.inserted.len(), fpr);
+        let mut filter_std = BloomFilter::<_, StdHasher>::new(dataset.inserted.len(), fpr);
 
         for item in &dataset.inserted {
             filter_ahash.insert(item);
             filter_xxhash.insert(item);
             filter_murmur3.insert(item);
+            filter_std.insert(item);
         }
 
         // Query only items we know are absent
@@ -265,6 +309,16 @@ fn hasher_negative_query_comparison(c: &mut Criterion) {
                 });
             },
         );
+
+        // StdHasher
+        group.bench_with_input(BenchmarkId::new("std", size), query_items, |b, items| {
+            let mut idx = 0;
+            b.iter(|| {
+                let item = &items[idx % items.len()];
+                idx += 1;
+                black_box(filter_std.contains(black_box(item)))
+            });
+        });
     }
 
     group.finish();
@@ -281,39 +335,54 @@ fn hasher_zipfian_comparison(c: &mut Criterion) {
     let size = 100_000;
     let cardinality = 10_000;
     let alpha = 1.07; // Realistic for web traffic
-    let dataset = Dataset::zipfian(size, cardinality, alpha, 42);
+    let dataset = Dataset::zipfian(size, cardinality, alpha, bench_seed());
     let fpr = 0.01;
 
     // AHasher
     group.bench_function("ahash_insert", |b| {
+        let mut pool = BloomFilterPool::<_, AHasher>::new();
         b.iter(|| {
-            let mut filter = BloomFilter::<_, AHasher>::new(cardinality, fpr);
+            let mut filter = pool.acquire(cardinality, fpr);
             for item in &dataset.inserted {
                 filter.insert(black_box(item));
             }
-            black_box(filter)
+            pool.release(black_box(filter));
         });
     });
 
     // XXHasher
     group.bench_function("xxhash3_insert", |b| {
+        let mut pool = BloomFilterPool::<_, XXHasher>::new();
         b.iter(|| {
-            let mut filter = BloomFilter::<_, XXHasher>::new(cardinality, fpr);
+            let mut filter = pool.acquire(cardinality, fpr);
             for item in &dataset.inserted {
                 filter.insert(black_box(item));
             }
-            black_box(filter)
+            pool.release(black_box(filter));
         });
     });
 
     // Murmur3Hasher
     group.bench_function("murmur3_insert", |b| {
+        let mut pool = BloomFilterPool::<_, Murmur3Hasher>::new();
+        b.iter(|| {
+            let mut filter = pool.acquire(cardinality, fpr);
+            for item in &dataset.inserted {
+                filter.insert(black_box(item));
+            }
+            pool.release(black_box(filter));
+        });
+    });
+
+    // StdHasher
+    group.bench_function("std_insert", |b| {
+        let mut pool = BloomFilterPool::<_, StdHasher>::new();
         b.iter(|| {
-            let mut filter = BloomFilter::<_, Murmur3Hasher>::new(cardinality, fpr);
+            let mut filter = pool.acquire(cardinality, fpr);
             for item in &dataset.inserted {
                 filter.insert(black_box(item));
             }
-            black_box(filter)
+            pool.release(black_box(filter));
         });
     });
 
@@ -0,0 +1,149 @@
+/// Pre-hashing vs Direct Hashing Benchmarks
+///
+/// Compares the two ways a string key reaches the hasher:
+/// - prehash: `insert` / `contains`, which fold the key into an 8-byte `DefaultHasher` digest
+///   first
+/// - direct: `insert_bytes` / `contains_bytes`, which hash the key's bytes as-is
+///
+/// Metrics: Insert throughput, query throughput, empirical FPR (printed once per run)
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use sketches::benchmarks::{Dataset, bench_seed};
+use sketches::filters::bloom::BloomFilter;
+use sketches::filters::traits::ApproximateMembershipQuery;
+use sketches::hashing::AHasher;
+use std::hint::black_box;
+
+type Filter = BloomFilter<String, AHasher>;
+
+const FPR: f64 = 0.01;
+
+/// Long, mostly shared-prefix keys, as URL or path keys are in practice.
+fn string_keys(items: &[u64]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "https://example.com/api/v2/users/{:020}/profile/settings",
+                item
+            )
+        })
+        .collect()
+}
+
+struct StringDataset {
+    inserted: Vec<String>,
+    queries_absent: Vec<String>,
+}
+
+impl StringDataset {
+    fn new(size: usize) -> Self {
+        let dataset = Dataset::uniform(size, bench_seed());
+        StringDataset {
+            inserted: string_keys(&dataset.inserted),
+            queries_absent: string_keys(&dataset.queries_absent),
+        }
+    }
+
+    fn prehashed_filter(&self) -> Filter {
+        let mut filter = Filter::new(self.inserted.len(), FPR);
+        for key in &self.inserted {
+            filter.insert(key);
+        }
+        filter
+    }
+
+    fn direct_filter(&self) -> Filter {
+        let mut filter = Filter::new(self.inserted.len(), FPR);
+        for key in &self.inserted {
+            filter.insert_bytes(key.as_bytes());
+        }
+        filter
+    }
+}
+
+// ============================================================================
+// Insert Benchmarks
+// ============================================================================
+
+fn prehash_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prehash_vs_direct_insert");
+
+    for size in [10_000, 100_000] {
+        let dataset = StringDataset::new(size);
+
+        group.bench_with_input(BenchmarkId::new("prehash", size), &dataset, |b, dataset| {
+            b.iter(|| black_box(dataset.prehashed_filter()));
+        });
+
+        group.bench_with_input(BenchmarkId::new("direct", size), &dataset, |b, dataset| {
+            b.iter(|| black_box(dataset.direct_filter()));
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// Query Benchmarks
+// ============================================================================
+
+fn prehash_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prehash_vs_direct_query");
+
+    for size in [10_000, 100_000] {
+        let dataset = StringDataset::new(size);
+        let prehashed = dataset.prehashed_filter();
+        let direct = dataset.direct_filter();
+
+        let prehashed_fpr = empirical_fpr(&dataset.queries_absent, |key| prehashed.contains(key));
+        let direct_fpr = empirical_fpr(&dataset.queries_absent, |key| {
+            direct.contains_bytes(key.as_bytes())
+        });
+        println!(
+            "size {}: empirical FPR prehash {:.4}% vs direct {:.4}% (target {:.2}%)",
+            size,
+            prehashed_fpr * 100.0,
+            direct_fpr * 100.0,
+            FPR * 100.0
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("prehash", size),
+            &dataset.queries_absent,
+            |b, keys| {
+                let mut idx = 0;
+                b.iter(|| {
+                    let key = &keys[idx % keys.len()];
+                    idx += 1;
+                    black_box(prehashed.contains(black_box(key)))
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("direct", size),
+            &dataset.queries_absent,
+            |b, keys| {
+                let mut idx = 0;
+                b.iter(|| {
+                    let key = &keys[idx % keys.len()];
+                    idx += 1;
+                    black_box(direct.contains_bytes(black_box(key.as_bytes())))
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn empirical_fpr(absent: &[String], contains: impl Fn(&String) -> bool) -> f64 {
+    absent.iter().filter(|key| contains(key)).count() as f64 / absent.len() as f64
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+criterion_group!(benches, prehash_insert, prehash_query);
+criterion_main!(benches);
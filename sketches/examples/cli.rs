@@ -0,0 +1,82 @@
+/// Minimal Bloom Filter CLI Example
+///
+/// Keeps a filter on disk and inserts or queries keys given on the command line:
+///
+/// ```text
+/// cargo run --example cli -- keys.bloom insert alice bob carol
+/// cargo run --example cli -- keys.bloom query alice mallory
+/// ```
+///
+/// Demonstrates:
+/// - Creating the filter on first use and persisting it with `as_bytes`
+/// - Loading it back with `from_bytes`
+/// - Byte-slice keys via `insert_bytes` / `contains_bytes`
+use sketches::filters::bloom::BloomFilter;
+use sketches::filters::traits::ApproximateMembershipQuery;
+use sketches::hashing::XXHasher;
+use std::path::Path;
+use std::process::ExitCode;
+
+type Filter = BloomFilter<Vec<u8>, XXHasher>;
+
+const CAPACITY: usize = 100_000;
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, command, keys) = match args.as_slice() {
+        [path, command, keys @ ..] if !keys.is_empty() => (Path::new(path), command, keys),
+        _ => return usage(),
+    };
+
+    let mut filter = match load(path) {
+        Ok(filter) => filter,
+        Err(err) => {
+            eprintln!("Cannot load {}: {}", path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match command.as_str() {
+        "insert" => {
+            for key in keys {
+                filter.insert_bytes(key.as_bytes());
+            }
+            if let Err(err) = std::fs::write(path, filter.as_bytes()) {
+                eprintln!("Cannot write {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+            println!(
+                "Inserted {} keys ({} total, current FPR {:.4}%)",
+                keys.len(),
+                filter.len(),
+                filter.current_false_positive_rate() * 100.0
+            );
+        }
+        "query" => {
+            for key in keys {
+                let verdict = if filter.contains_bytes(key.as_bytes()) {
+                    "probably present"
+                } else {
+                    "absent"
+                };
+                println!("{}: {}", key, verdict);
+            }
+        }
+        _ => return usage(),
+    }
+    ExitCode::SUCCESS
+}
+
+/// Loads the filter at `path`, or creates an empty one if the file doesn't exist yet.
+fn load(path: &Path) -> Result<Filter, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(Filter::new(CAPACITY, FALSE_POSITIVE_RATE));
+    }
+    Ok(Filter::from_bytes(&std::fs::read(path)?)?)
+}
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: cli <filter-file> (insert|query) <keys...>");
+    ExitCode::FAILURE
+}
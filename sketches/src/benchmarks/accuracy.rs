@@ -0,0 +1,288 @@
+/// Accuracy helpers for validating probabilistic guarantees
+///
+/// Measures empirical error rates against a [`Dataset`] so that regressions (e.g. a hasher
+/// producing correlated base hashes) show up as drift from the configured targets.
+use super::Dataset;
+use crate::filters::bloom::BloomFilter;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::{AHasher, Hasher64, Murmur3Hasher, XXHasher};
+use std::collections::HashSet;
+
+/// Build a Bloom filter over `dataset.inserted` and measure its FPR on `dataset.queries_absent`
+pub fn empirical_fpr<H: Hasher64>(dataset: &Dataset, fpr: f64) -> f64 {
+    let mut filter = BloomFilter::<_, H>::new(dataset.inserted.len(), fpr);
+    for item in &dataset.inserted {
+        filter.insert(item);
+    }
+
+    let false_positives = dataset
+        .queries_absent
+        .iter()
+        .filter(|item| filter.contains(item))
+        .count();
+    false_positives as f64 / dataset.queries_absent.len() as f64
+}
+
+/// Measure the empirical FPR of a Bloom filter built with each built-in hasher
+///
+/// Returns `(hasher_name, empirical_fpr)` pairs, using the same names as the hasher
+/// comparison benchmarks.
+///
+/// # Examples
+///
+/// ```
+/// use sketches::benchmarks::{Dataset, fpr_across_hashers};
+///
+/// let dataset = Dataset::uniform(10_000, 42);
+/// for (name, fpr) in fpr_across_hashers(&dataset, 0.01) {
+///     println!("{}: {:.4}", name, fpr);
+/// }
+/// ```
+pub fn fpr_across_hashers(dataset: &Dataset, fpr: f64) -> Vec<(&'static str, f64)> {
+    vec![
+        ("ahash", empirical_fpr::<AHasher>(dataset, fpr)),
+        ("xxhash3", empirical_fpr::<XXHasher>(dataset, fpr)),
+        ("murmur3", empirical_fpr::<Murmur3Hasher>(dataset, fpr)),
+    ]
+}
+
+/// Measure the empirical FPR for every combination of capacity and configured FPR
+///
+/// Each capacity gets a fresh [`Dataset::uniform`] of that size generated from `seed`, shared
+/// by all the FPR targets, and filters hash with [`AHasher`]. Returns
+/// `(capacity, configured_fpr, empirical_fpr)` triples in sweep order, capacity-major, so the
+/// same arguments always reproduce the same table.
+pub fn fpr_sweep(capacities: &[usize], fprs: &[f64], seed: u64) -> Vec<(usize, f64, f64)> {
+    capacities
+        .iter()
+        .flat_map(|&capacity| {
+            let dataset = Dataset::uniform(capacity, seed);
+            fprs.iter()
+                .map(|&fpr| (capacity, fpr, empirical_fpr::<AHasher>(&dataset, fpr)))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Outcome counts of membership queries against ground truth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    /// Present items reported present
+    pub true_positives: usize,
+    /// Present items reported absent (must be 0 for any AMQ)
+    pub false_negatives: usize,
+    /// Absent items reported absent
+    pub true_negatives: usize,
+    /// Absent items reported present
+    pub false_positives: usize,
+}
+
+impl ConfusionMatrix {
+    /// Fraction of positive answers that were correct
+    pub fn precision(&self) -> f64 {
+        self.true_positives as f64 / (self.true_positives + self.false_positives) as f64
+    }
+
+    /// Fraction of present items reported present (1.0 for any AMQ)
+    pub fn recall(&self) -> f64 {
+        self.true_positives as f64 / (self.true_positives + self.false_negatives) as f64
+    }
+
+    /// Fraction of absent items reported present
+    pub fn false_positive_rate(&self) -> f64 {
+        self.false_positives as f64 / (self.false_positives + self.true_negatives) as f64
+    }
+}
+
+impl std::fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Confusion Matrix:\n  TP: {}  FN: {}\n  FP: {}  TN: {}\n  Precision: {:.4}  Recall: {:.4}  FPR: {:.4}",
+            self.true_positives,
+            self.false_negatives,
+            self.false_positives,
+            self.true_negatives,
+            self.precision(),
+            self.recall(),
+            self.false_positive_rate()
+        )
+    }
+}
+
+/// Insert `dataset.inserted` into `filter` and classify every query in both query sets
+///
+/// Unlike [`empirical_fpr`], this also checks the present queries, so a regression introducing
+/// false negatives shows up as a non-zero `false_negatives`.
+pub fn amq_confusion<F: ApproximateMembershipQuery<u64>>(
+    mut filter: F,
+    dataset: &Dataset,
+) -> ConfusionMatrix {
+    for item in &dataset.inserted {
+        filter.insert(item);
+    }
+
+    let true_positives = dataset
+        .queries_present
+        .iter()
+        .filter(|item| filter.contains(item))
+        .count();
+    let false_positives = dataset
+        .queries_absent
+        .iter()
+        .filter(|item| filter.contains(item))
+        .count();
+    ConfusionMatrix {
+        true_positives,
+        false_negatives: dataset.queries_present.len() - true_positives,
+        true_negatives: dataset.queries_absent.len() - false_positives,
+        false_positives,
+    }
+}
+
+/// Check that `filter` finds every item of `dataset.inserted` and `dataset.queries_present`
+///
+/// Returns the distinct missing items, in the order they were first queried, so a failure
+/// shows exactly which keys broke the no-false-negatives guarantee. The filter is expected to
+/// already hold the dataset; use [`amq_confusion`] to build and classify in one go.
+///
+/// # Examples
+///
+/// ```
+/// use sketches::benchmarks::{Dataset, assert_no_false_negatives};
+/// use sketches::filters::bloom::BloomFilter;
+/// use sketches::filters::traits::ApproximateMembershipQuery;
+/// use sketches::hashing::XXHasher;
+///
+/// let dataset = Dataset::uniform(1_000, 42);
+/// let mut filter = BloomFilter::<u64, XXHasher>::new(1_000, 0.01);
+/// for item in &dataset.inserted {
+///     filter.insert(item);
+/// }
+/// assert_eq!(assert_no_false_negatives(&filter, &dataset), Ok(()));
+/// ```
+pub fn assert_no_false_negatives<F: ApproximateMembershipQuery<u64>>(
+    filter: &F,
+    dataset: &Dataset,
+) -> Result<(), Vec<u64>> {
+    let mut reported = HashSet::new();
+    let missing: Vec<u64> = dataset
+        .inserted
+        .iter()
+        .chain(&dataset.queries_present)
+        .filter(|item| !filter.contains(item) && reported.insert(**item))
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amq_confusion_has_no_false_negatives() {
+        let dataset = Dataset::uniform(50_000, 7);
+        let filter = BloomFilter::<u64, XXHasher>::new(dataset.inserted.len(), 0.01);
+        let matrix = amq_confusion(filter, &dataset);
+
+        assert_eq!(matrix.false_negatives, 0);
+        assert_eq!(matrix.true_positives, dataset.queries_present.len());
+        assert_eq!(
+            matrix.true_negatives + matrix.false_positives,
+            dataset.queries_absent.len()
+        );
+        assert_eq!(matrix.recall(), 1.0);
+        assert!(matrix.to_string().contains("  FN: 0\n"));
+        assert!(matrix.false_positive_rate() < 0.02);
+    }
+
+    /// Reports odd items absent once they have been inserted
+    struct DropsOdd(BloomFilter<u64, XXHasher>);
+
+    impl ApproximateMembershipQuery<u64> for DropsOdd {
+        fn insert(&mut self, item: &u64) {
+            self.0.insert(item);
+        }
+        fn contains(&self, item: &u64) -> bool {
+            item.is_multiple_of(2) && self.0.contains(item)
+        }
+        fn false_positive_rate(&self) -> f64 {
+            self.0.false_positive_rate()
+        }
+        fn capacity(&self) -> usize {
+            self.0.capacity()
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn test_assert_no_false_negatives() {
+        let dataset = Dataset::zipfian(10_000, 500, 1.1, 7);
+        let mut filter = BloomFilter::<u64, XXHasher>::new(500, 0.01);
+        for item in &dataset.inserted {
+            filter.insert(item);
+        }
+        assert_eq!(assert_no_false_negatives(&filter, &dataset), Ok(()));
+
+        let mut broken = DropsOdd(BloomFilter::new(500, 0.01));
+        for item in &dataset.inserted {
+            broken.insert(item);
+        }
+        let missing = assert_no_false_negatives(&broken, &dataset).unwrap_err();
+        let expected: HashSet<u64> = dataset
+            .inserted
+            .iter()
+            .copied()
+            .filter(|item| !item.is_multiple_of(2))
+            .collect();
+        assert_eq!(
+            missing.len(),
+            expected.len(),
+            "Missing keys must be distinct"
+        );
+        assert_eq!(missing.into_iter().collect::<HashSet<_>>(), expected);
+    }
+
+    #[test]
+    fn test_fpr_sweep_tracks_configured_rates() {
+        let sweep = fpr_sweep(&[100_000, 200_000], &[0.1, 0.05, 0.01], 42);
+
+        assert_eq!(sweep.len(), 6);
+        assert_eq!(sweep[0].0, 100_000);
+        assert_eq!(sweep[5].0, 200_000);
+        for (capacity, configured, empirical) in sweep {
+            assert!(
+                (empirical - configured).abs() <= configured * 0.3,
+                "capacity={} configured={} empirical={:.4}",
+                capacity,
+                configured,
+                empirical
+            );
+        }
+    }
+
+    #[test]
+    fn test_fpr_across_hashers_within_tolerance() {
+        let dataset = Dataset::uniform(100_000, 42);
+        let target = 0.01;
+        let results = fpr_across_hashers(&dataset, target);
+
+        assert_eq!(results.len(), 3);
+        for (name, empirical) in results {
+            assert!(
+                (empirical - target).abs() <= target * 0.3,
+                "{} drifted from target: {:.4} vs {:.4}",
+                name,
+                empirical,
+                target
+            );
+        }
+    }
+}
@@ -5,7 +5,9 @@
 use rand::Rng;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use rand_distr::{Distribution, Zipf};
+use std::collections::HashSet;
 
 /// A dataset for benchmarking with inserted items and query sets
 #[derive(Clone)]
@@ -29,7 +31,7 @@ impl Dataset {
     /// # Examples
     ///
     /// ```
-    /// use sketches_bench::benchmarks::datasets::Dataset;
+    /// use sketches::benchmarks::datasets::Dataset;
     ///
     /// let dataset = Dataset::uniform(10_000, 42);
     /// assert_eq!(dataset.inserted.len(), 10_000);
@@ -46,15 +48,7 @@ impl Dataset {
         let queries_present: Vec<u64> = inserted.iter().step_by(10).copied().take(n / 10).collect();
 
         // Generate items NOT in inserted set for negative queries
-        let inserted_set: std::collections::HashSet<u64> = inserted.iter().copied().collect();
-
-        let mut queries_absent = Vec::new();
-        while queries_absent.len() < n / 10 {
-            let item: u64 = rng.random();
-            if !inserted_set.contains(&item) {
-                queries_absent.push(item);
-            }
-        }
+        let queries_absent = generate_absent(&inserted, n / 10, rng.random());
 
         Dataset {
             inserted,
@@ -78,14 +72,14 @@ impl Dataset {
     /// # Examples
     ///
     /// ```
-    /// use sketches_bench::benchmarks::datasets::Dataset;
+    /// use sketches::benchmarks::datasets::Dataset;
     ///
     /// // Generate 100K insertions from a universe of 10K unique items
     /// // with Zipfian distribution (alpha=1.07 is realistic for web traffic)
     /// let dataset = Dataset::zipfian(100_000, 10_000, 1.07, 42);
     /// assert_eq!(dataset.inserted.len(), 100_000);
     /// // queries_present will sample from the inserted (with duplicates)
-    /// // queries_absent will be from the universe but not frequently inserted
+    /// // queries_absent are random values never inserted
     /// ```
     pub fn zipfian(n: usize, cardinality: usize, alpha: f64, seed: u64) -> Self {
         let mut rng = StdRng::seed_from_u64(seed);
@@ -97,27 +91,65 @@ impl Dataset {
         // Sample 10% for positive queries
         let queries_present: Vec<u64> = inserted.iter().step_by(10).copied().take(n / 10).collect();
 
-        // For negative queries, sample from the tail of the distribution
-        // (items that exist in universe but are rare/never inserted)
-        let inserted_set: std::collections::HashSet<u64> = inserted.iter().copied().collect();
-
-        let mut queries_absent = Vec::new();
-        let mut attempts = 0;
-        while queries_absent.len() < n / 10 && attempts < n * 2 {
-            let item = (cardinality as u64 / 2) + rng.random::<u64>() % (cardinality as u64 / 2);
-            if !inserted_set.contains(&item) {
-                queries_absent.push(item);
-            }
-            attempts += 1;
-        }
+        // Generate items NOT in inserted set for negative queries
+        let queries_absent = generate_absent(&inserted, n / 10, rng.random());
 
-        // Fill remaining with random items if needed
-        while queries_absent.len() < n / 10 {
-            let item: u64 = rng.random();
-            if !inserted_set.contains(&item) {
-                queries_absent.push(item);
-            }
+        Dataset {
+            inserted,
+            queries_present,
+            queries_absent,
         }
+    }
+
+    /// Generate a dataset mixing a small hot key set with a large cold one
+    ///
+    /// Models cache workloads: each of the `n` insertions comes from the hot set with
+    /// probability `hot_fraction` and from the cold set otherwise, uniformly within each set.
+    /// Unlike Zipfian skew, the two populations and their split are set directly.
+    ///
+    /// Hot keys are `0..hot_keys` and cold keys follow them (`hot_keys..hot_keys + cold_keys`),
+    /// so an item's population can be read off its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sketches::benchmarks::datasets::Dataset;
+    ///
+    /// // 90% of 100K accesses hit 100 hot keys, the rest spread over 1M cold ones
+    /// let dataset = Dataset::hot_cold(100, 1_000_000, 0.9, 100_000, 42);
+    /// assert_eq!(dataset.inserted.len(), 100_000);
+    /// assert_eq!(dataset.queries_absent.len(), 10_000);
+    /// ```
+    pub fn hot_cold(
+        hot_keys: usize,
+        cold_keys: usize,
+        hot_fraction: f64,
+        n: usize,
+        seed: u64,
+    ) -> Self {
+        assert!(hot_keys > 0 && cold_keys > 0, "Key sets must not be empty");
+        assert!(
+            (0.0..=1.0).contains(&hot_fraction),
+            "Hot fraction must be in [0, 1]"
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (hot, cold) = (hot_keys as u64, cold_keys as u64);
+
+        let inserted: Vec<u64> = (0..n)
+            .map(|_| {
+                if rng.random_bool(hot_fraction) {
+                    rng.random_range(0..hot)
+                } else {
+                    hot + rng.random_range(0..cold)
+                }
+            })
+            .collect();
+
+        // Sample 10% for positive queries
+        let queries_present: Vec<u64> = inserted.iter().step_by(10).copied().take(n / 10).collect();
+
+        // Generate items NOT in inserted set for negative queries
+        let queries_absent = generate_absent(&inserted, n / 10, rng.random());
 
         Dataset {
             inserted,
@@ -141,9 +173,32 @@ impl Dataset {
         Self::uniform(1_000_000, seed)
     }
 
+    /// Return a copy with `inserted` permuted deterministically by `seed`
+    ///
+    /// The query sets are kept as they are: shuffling doesn't change which items were
+    /// inserted, so they stay valid present/absent queries and results differ only by the
+    /// insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sketches::benchmarks::datasets::Dataset;
+    ///
+    /// let mut sorted = Dataset::uniform(1_000, 42);
+    /// sorted.inserted.sort_unstable();
+    /// let shuffled = sorted.shuffled(7);
+    /// assert_eq!(shuffled.queries_absent, sorted.queries_absent);
+    /// ```
+    pub fn shuffled(&self, seed: u64) -> Dataset {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut shuffled = self.clone();
+        shuffled.inserted.shuffle(&mut rng);
+        shuffled
+    }
+
     /// Get the actual cardinality (number of unique items)
     pub fn cardinality(&self) -> usize {
-        let set: std::collections::HashSet<u64> = self.inserted.iter().copied().collect();
+        let set: HashSet<u64> = self.inserted.iter().copied().collect();
         set.len()
     }
 
@@ -163,6 +218,59 @@ impl Dataset {
     }
 }
 
+/// Generate `count` distinct values that don't occur in `inserted`
+///
+/// Use it wherever false positives are measured, so every absent-query set is built the same
+/// way. Values are drawn uniformly from all of `u64` and are deterministic for a given `seed`.
+///
+/// # Examples
+///
+/// ```
+/// use sketches::benchmarks::datasets::generate_absent;
+///
+/// let inserted: Vec<u64> = (0..1_000).collect();
+/// let absent = generate_absent(&inserted, 500, 42);
+/// assert_eq!(absent.len(), 500);
+/// assert!(absent.iter().all(|item| !inserted.contains(item)));
+/// ```
+pub fn generate_absent(inserted: &[u64], count: usize, seed: u64) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut seen: HashSet<u64> = inserted.iter().copied().collect();
+    let mut absent = Vec::with_capacity(count);
+    while absent.len() < count {
+        let item: u64 = rng.random();
+        if seen.insert(item) {
+            absent.push(item);
+        }
+    }
+    absent
+}
+
+/// Environment variable overriding the seed benchmarks build their datasets from
+pub const BENCH_SEED_VAR: &str = "SKETCHES_BENCH_SEED";
+
+/// Seed for benchmark datasets: `SKETCHES_BENCH_SEED` if set, 42 otherwise
+///
+/// Running the suite under several seeds shows whether a measurement (an empirical false
+/// positive rate, say) depends on the particular dataset drawn.
+///
+/// # Panics
+///
+/// If the variable is set to something other than an unsigned 64-bit integer, so a typo
+/// doesn't silently fall back to the default.
+pub fn bench_seed() -> u64 {
+    parse_bench_seed(std::env::var(BENCH_SEED_VAR).ok().as_deref())
+}
+
+fn parse_bench_seed(value: Option<&str>) -> u64 {
+    match value {
+        None => 42,
+        Some(value) => value.trim().parse().unwrap_or_else(|_| {
+            panic!("{BENCH_SEED_VAR} must be an unsigned integer, got {value:?}")
+        }),
+    }
+}
+
 /// Statistics about a dataset
 #[derive(Debug, Clone)]
 pub struct DatasetStats {
@@ -196,6 +304,19 @@ impl std::fmt::Display for DatasetStats {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bench_seed_parsing() {
+        assert_eq!(parse_bench_seed(None), 42);
+        assert_eq!(parse_bench_seed(Some("7")), 7);
+        assert_eq!(parse_bench_seed(Some(" 18446744073709551615\n")), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "SKETCHES_BENCH_SEED must be an unsigned integer")]
+    fn test_bench_seed_rejects_garbage() {
+        parse_bench_seed(Some("forty-two"));
+    }
+
     #[test]
     fn test_uniform_dataset() {
         let dataset = Dataset::uniform(10_000, 42);
@@ -205,8 +326,7 @@ mod tests {
         assert_eq!(dataset.queries_absent.len(), 1_000);
 
         // Verify queries_present are actually in inserted
-        let inserted_set: std::collections::HashSet<u64> =
-            dataset.inserted.iter().copied().collect();
+        let inserted_set: HashSet<u64> = dataset.inserted.iter().copied().collect();
 
         for item in &dataset.queries_present {
             assert!(inserted_set.contains(item));
@@ -218,6 +338,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_absent() {
+        // A dense block of small values, plus values a uniform generator's stream would hit
+        let mut inserted: Vec<u64> = (0..10_000).collect();
+        inserted.extend(StdRng::seed_from_u64(7).random_iter::<u64>().take(1_000));
+
+        let absent = generate_absent(&inserted, 5_000, 7);
+        assert_eq!(absent.len(), 5_000);
+        let inserted_set: HashSet<u64> = inserted.iter().copied().collect();
+        assert!(absent.iter().all(|item| !inserted_set.contains(item)));
+        assert_eq!(absent.iter().collect::<HashSet<_>>().len(), 5_000);
+        assert_eq!(absent, generate_absent(&inserted, 5_000, 7));
+        assert!(generate_absent(&inserted, 0, 7).is_empty());
+    }
+
+    #[test]
+    fn test_hot_cold_dataset() {
+        let hot_keys = 50;
+        let dataset = Dataset::hot_cold(hot_keys, 100_000, 0.8, 100_000, 42);
+
+        assert_eq!(dataset.inserted.len(), 100_000);
+        let hot_accesses = dataset
+            .inserted
+            .iter()
+            .filter(|&&item| item < hot_keys as u64)
+            .count();
+        let realized = hot_accesses as f64 / dataset.inserted.len() as f64;
+        assert!((realized - 0.8).abs() < 0.01, "hot fraction {}", realized);
+        assert!(dataset.inserted.iter().all(|&item| item < 100_050));
+
+        let inserted_set: HashSet<u64> = dataset.inserted.iter().copied().collect();
+        assert!(
+            dataset
+                .queries_present
+                .iter()
+                .all(|item| inserted_set.contains(item))
+        );
+        assert!(
+            dataset
+                .queries_absent
+                .iter()
+                .all(|item| !inserted_set.contains(item))
+        );
+    }
+
     #[test]
     fn test_zipfian_dataset() {
         let dataset = Dataset::zipfian(100_000, 10_000, 1.07, 42);
@@ -247,6 +412,23 @@ mod tests {
         assert_eq!(stats.queries_absent, 100);
     }
 
+    #[test]
+    fn test_shuffled_permutes_inserted() {
+        let dataset = Dataset::zipfian(10_000, 1_000, 1.07, 42);
+        let shuffled = dataset.shuffled(7);
+
+        assert_ne!(shuffled.inserted, dataset.inserted);
+        let mut original = dataset.inserted.clone();
+        let mut permuted = shuffled.inserted.clone();
+        original.sort_unstable();
+        permuted.sort_unstable();
+        assert_eq!(original, permuted);
+
+        assert_eq!(shuffled.queries_present, dataset.queries_present);
+        assert_eq!(shuffled.queries_absent, dataset.queries_absent);
+        assert_eq!(dataset.shuffled(7).inserted, shuffled.inserted);
+    }
+
     #[test]
     fn test_convenience_constructors() {
         let small = Dataset::small(42);
@@ -0,0 +1,57 @@
+/// Exact reference answers for validating approximate sketches
+///
+/// These are computed in memory proportional to the input, so they are only meant for tests
+/// and benchmarks against which heavy-hitter and frequency sketches are checked.
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Return the `k` most frequent values in `items` with their counts, most frequent first
+///
+/// Ties are broken by the smaller value, so the result is deterministic. Returns fewer than
+/// `k` pairs if `items` has fewer distinct values.
+///
+/// # Examples
+///
+/// ```
+/// use sketches::benchmarks::exact_top_k;
+///
+/// let top = exact_top_k(&[3, 1, 3, 2, 3, 1], 2);
+/// assert_eq!(top, vec![(3, 3), (1, 2)]);
+/// ```
+pub fn exact_top_k(items: &[u64], k: usize) -> Vec<(u64, u64)> {
+    let mut counts: HashMap<u64, u64> = HashMap::new();
+    for &item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+
+    let mut ranked: Vec<(u64, u64)> = counts.into_iter().collect();
+    let rank = |&(value, count): &(u64, u64)| (Reverse(count), value);
+    if k == 0 {
+        return Vec::new();
+    } else if k < ranked.len() {
+        ranked.select_nth_unstable_by_key(k - 1, rank);
+        ranked.truncate(k);
+    }
+    ranked.sort_unstable_by_key(rank);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_ranking() {
+        // 7 x5, 4 x4, 9 x4, 1 x2, 8 x1
+        let items = [7, 4, 9, 7, 1, 4, 9, 7, 8, 4, 9, 7, 1, 4, 9, 7];
+
+        assert_eq!(exact_top_k(&items, 1), vec![(7, 5)]);
+        assert_eq!(exact_top_k(&items, 3), vec![(7, 5), (4, 4), (9, 4)]);
+        assert_eq!(
+            exact_top_k(&items, 10),
+            vec![(7, 5), (4, 4), (9, 4), (1, 2), (8, 1)]
+        );
+        assert!(exact_top_k(&items, 0).is_empty());
+        assert!(exact_top_k(&[], 3).is_empty());
+    }
+}
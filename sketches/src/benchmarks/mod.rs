@@ -1,6 +1,15 @@
 /// Benchmark utilities and common datasets
 ///
 /// Provides shared datasets and workloads for consistent cross-crate benchmarking.
+pub mod accuracy;
 pub mod datasets;
+pub mod ground_truth;
+pub mod pool;
 
-pub use datasets::{Dataset, DatasetStats};
+pub use accuracy::{
+    ConfusionMatrix, amq_confusion, assert_no_false_negatives, empirical_fpr, fpr_across_hashers,
+    fpr_sweep,
+};
+pub use datasets::{BENCH_SEED_VAR, Dataset, DatasetStats, bench_seed, generate_absent};
+pub use ground_truth::exact_top_k;
+pub use pool::BloomFilterPool;
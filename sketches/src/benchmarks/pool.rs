@@ -0,0 +1,98 @@
+/// Reusable filters for throughput benchmarks
+///
+/// Building a filter allocates and zeroes its whole bit array, which for large filters costs
+/// more than the insertions being measured. Filters released to a pool are cleared and handed
+/// out again, so a benchmark iteration only pays for clearing the bits.
+use crate::filters::bloom::BloomFilter;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::hash::Hash;
+
+/// A pool of cleared Bloom filters, kept by capacity and false positive rate
+///
+/// # Examples
+///
+/// ```
+/// use sketches::benchmarks::BloomFilterPool;
+/// use sketches::filters::traits::ApproximateMembershipQuery;
+/// use sketches::hashing::AHasher;
+///
+/// let mut pool = BloomFilterPool::<u64, AHasher>::new();
+/// for _ in 0..3 {
+///     let mut filter = pool.acquire(1000, 0.01);
+///     filter.insert(&42);
+///     pool.release(filter);
+/// }
+/// assert_eq!(pool.idle(), 1);
+/// ```
+pub struct BloomFilterPool<T, H: Hasher64> {
+    idle: Vec<BloomFilter<T, H>>,
+}
+
+impl<T: Hash, H: Hasher64> BloomFilterPool<T, H> {
+    pub fn new() -> Self {
+        Self { idle: Vec::new() }
+    }
+
+    /// Return an empty filter for `capacity` items at `false_positive_rate`, reusing a released
+    /// one with the same parameters if there is one
+    pub fn acquire(&mut self, capacity: usize, false_positive_rate: f64) -> BloomFilter<T, H> {
+        let reusable = self.idle.iter().position(|filter| {
+            filter.capacity() == capacity && filter.false_positive_rate() == false_positive_rate
+        });
+        match reusable {
+            Some(index) => self.idle.swap_remove(index),
+            None => BloomFilter::new(capacity, false_positive_rate),
+        }
+    }
+
+    /// Clear `filter` and keep it for a later [`acquire`](Self::acquire)
+    pub fn release(&mut self, mut filter: BloomFilter<T, H>) {
+        filter.clear();
+        self.idle.push(filter);
+    }
+
+    /// Number of filters waiting to be reused
+    pub fn idle(&self) -> usize {
+        self.idle.len()
+    }
+}
+
+impl<T: Hash, H: Hasher64> Default for BloomFilterPool<T, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_reacquired_filter_is_empty_and_sized() {
+        let mut pool = BloomFilterPool::<u64, AHasher>::new();
+        let mut filter = pool.acquire(1000, 0.01);
+        let num_bits = filter.num_bits();
+        for i in 0..1000u64 {
+            filter.insert(&i);
+        }
+        pool.release(filter);
+
+        let other = pool.acquire(5000, 0.01);
+        assert_eq!(
+            pool.idle(),
+            1,
+            "Different parameters must not reuse the filter"
+        );
+        assert_eq!(other.capacity(), 5000);
+
+        let reused = pool.acquire(1000, 0.01);
+        assert_eq!(pool.idle(), 0);
+        assert!(reused.is_empty());
+        assert_eq!(reused.set_bits(), 0);
+        assert_eq!(reused.num_bits(), num_bits);
+        assert_eq!(reused.false_positive_rate(), 0.01);
+        assert!(!reused.contains(&1));
+    }
+}
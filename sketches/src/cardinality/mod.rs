@@ -0,0 +1,3 @@
+mod per_key;
+
+pub use per_key::DistinctPerKey;
@@ -0,0 +1,161 @@
+use crate::hashing::{Hasher64, item_bytes, row_seeds};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Seed of the hasher that places values into registers; rows take theirs from `row_seeds`.
+const VALUE_SEED: u64 = 0xDA7A_5EED;
+
+/// Approximate number of distinct values per key, in memory independent of the number of keys.
+///
+/// The layout is a Count-Min sketch whose cells are HyperLogLog register blocks: `depth` rows
+/// of `width` blocks, each holding `2^precision` registers. A key is hashed to one block per
+/// row and its values are recorded in all of them; the estimate is the smallest of the blocks'
+/// HyperLogLog estimates.
+///
+/// Keys that share a block in every row see each other's values, so estimates can only be
+/// inflated by collisions, never deflated. Keeping one HyperLogLog per key avoids this, but
+/// its memory grows with the number of keys; here it is fixed at
+/// `depth * width * 2^precision` bytes. Make `width` a few times the number of heavy keys to
+/// keep collisions rare.
+pub struct DistinctPerKey<K, V, H: Hasher64> {
+    registers: Vec<u8>,
+    width: usize,
+    depth: usize,
+    precision: u32,
+    row_hashers: Vec<H>,
+    value_hasher: H,
+    _phantom_data: PhantomData<(K, V)>,
+}
+
+impl<K: Hash, V: Hash, H: Hasher64> DistinctPerKey<K, V, H> {
+    /// Creates an empty sketch. `precision` must be in `4..=16`; each block's relative standard
+    /// error is about `1.04 / sqrt(2^precision)`.
+    pub fn new(width: usize, depth: usize, precision: u32) -> Self {
+        assert!(width > 0, "Width must be greater than 0");
+        assert!(depth > 0, "Depth must be greater than 0");
+        assert!(
+            (4..=16).contains(&precision),
+            "Precision must be between 4 and 16"
+        );
+        DistinctPerKey {
+            registers: vec![0; (width * depth) << precision],
+            width,
+            depth,
+            precision,
+            row_hashers: row_seeds(depth).map(H::with_seed).collect(),
+            value_hasher: H::with_seed(VALUE_SEED),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Records that `value` occurred with `key`.
+    pub fn observe(&mut self, key: &K, value: &V) {
+        let hash = self.value_hasher.hash(&item_bytes(value));
+        let register = (hash >> (64 - self.precision)) as usize;
+        // Rank of the first set bit in the remaining bits, capped so it fits the width
+        let rank = ((hash << self.precision).leading_zeros() + 1).min(65 - self.precision) as u8;
+        let blocks: Vec<usize> = self.blocks(key).collect();
+        for block in blocks {
+            let slot = &mut self.registers[block + register];
+            *slot = (*slot).max(rank);
+        }
+    }
+
+    /// Estimates the number of distinct values observed with `key`.
+    pub fn estimate_distinct(&self, key: &K) -> f64 {
+        self.blocks(key)
+            .map(|block| self.block_estimate(block))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Start offsets of the register blocks `key` maps to, one per row.
+    fn blocks(&self, key: &K) -> impl Iterator<Item = usize> + use<'_, K, V, H> {
+        let bytes = item_bytes(key);
+        let block_len = 1usize << self.precision;
+        self.row_hashers
+            .iter()
+            .enumerate()
+            .map(move |(row, hasher)| {
+                let column = (hasher.hash(&bytes) % self.width as u64) as usize;
+                (row * self.width + column) * block_len
+            })
+    }
+
+    /// HyperLogLog estimate of one block, with linear counting for small cardinalities.
+    fn block_estimate(&self, block: usize) -> f64 {
+        let registers = &self.registers[block..block + (1 << self.precision)];
+        let m = registers.len() as f64;
+        let sum: f64 = registers.iter().map(|&r| (-(r as f64)).exp2()).sum();
+        let raw = alpha(registers.len()) * m * m / sum;
+
+        let zeros = registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Bias-correction constant for `m` registers.
+fn alpha(m: usize) -> f64 {
+    match m {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213 / (1.0 + 1.079 / m as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::XXHasher;
+
+    #[test]
+    fn test_per_key_estimates() {
+        let mut sketch = DistinctPerKey::<u64, u64, XXHasher>::new(64, 4, 10);
+        // Key k has 200 * (k + 1) distinct values, each observed three times
+        for key in 0..20u64 {
+            for value in 0..200 * (key + 1) {
+                for _ in 0..3 {
+                    sketch.observe(&key, &(key << 32 | value));
+                }
+            }
+        }
+
+        for key in 0..20u64 {
+            let truth = 200.0 * (key + 1) as f64;
+            let estimate = sketch.estimate_distinct(&key);
+            let error = (estimate - truth).abs() / truth;
+            assert!(
+                error < 0.15,
+                "key={} estimate={:.0} truth={}",
+                key,
+                estimate,
+                truth
+            );
+        }
+    }
+
+    #[test]
+    fn test_unseen_key_and_collisions_only_inflate() {
+        let mut sketch = DistinctPerKey::<u64, u64, XXHasher>::new(1, 1, 8);
+        assert_eq!(sketch.estimate_distinct(&1), 0.0);
+
+        // A single block: every key sees every value
+        for value in 0..1000u64 {
+            sketch.observe(&(value % 2), &value);
+        }
+        assert!(sketch.estimate_distinct(&0) > 800.0);
+        assert_eq!(sketch.estimate_distinct(&0), sketch.estimate_distinct(&1));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::filters::bloom::BloomFilter;
+use crate::hashing::{Hasher64, hash_item};
+use std::hash::Hash;
+
+/// Yields the items of `iter` that have not (probably) been yielded before.
+///
+/// Seen items are remembered in a Bloom filter for `capacity` distinct items at
+/// `false_positive_rate`, so memory stays bounded however long the stream is. Duplicates are
+/// always dropped, but a false positive also drops a genuinely new item: each new item is lost
+/// with probability about `false_positive_rate` while fewer than `capacity` distinct items
+/// have gone through, and increasingly often past it. Each item is hashed once.
+///
+/// # Examples
+///
+/// ```
+/// use sketches::dedup::approx_dedup;
+/// use sketches::hashing::AHasher;
+///
+/// let unique: Vec<u32> = approx_dedup::<_, AHasher>([3, 1, 3, 2, 1], 100, 0.001).collect();
+/// assert_eq!(unique, vec![3, 1, 2]);
+/// ```
+pub fn approx_dedup<I, H>(
+    iter: I,
+    capacity: usize,
+    false_positive_rate: f64,
+) -> impl Iterator<Item = I::Item>
+where
+    I: IntoIterator,
+    I::Item: Hash,
+    H: Hasher64,
+{
+    let mut seen = BloomFilter::<I::Item, H>::new(capacity, false_positive_rate);
+    iter.into_iter().filter(move |item| {
+        let hashes = hash_item::<H, _>(item, seen.base_seeds());
+        if seen.contains_precomputed(&hashes) {
+            return false;
+        }
+        seen.insert_precomputed(&hashes);
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_drops_duplicates() {
+        // 10_000 distinct values, each repeated up to 5 times
+        let stream = (0..50_000u64).map(|i| (i * 7919) % 10_000);
+        let unique: Vec<u64> = approx_dedup::<_, AHasher>(stream, 10_000, 0.01).collect();
+
+        let distinct: HashSet<u64> = unique.iter().copied().collect();
+        assert_eq!(distinct.len(), unique.len(), "A duplicate was yielded");
+        assert!(
+            unique.len() > 9900,
+            "Only {} of 10000 distinct items yielded",
+            unique.len()
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use super::BloomFilter;
+use crate::filters::keylog::KeyLog;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use rand::Rng;
+use std::hash::Hash;
+
+/// Default number of inserts between clustering checks.
+const DEFAULT_CHECK_INTERVAL: usize = 256;
+/// Default dispersion above which the filter rehashes.
+const DEFAULT_MAX_DISPERSION: f64 = 4.0;
+/// Number of histogram buckets used to measure clustering.
+const HISTOGRAM_BUCKETS: usize = 64;
+
+/// A Bloom filter that detects pathologically clustered fill and rehashes under a fresh seed.
+///
+/// Every inserted key is kept in a [`KeyLog`]. After every `check_interval` inserts, the
+/// filter's [`fill_histogram`](BloomFilter::fill_histogram) is compared against what uniformly
+/// distributed hashes would produce. If the dispersion index (observed variance over the
+/// expected binomial variance, ~1 for healthy fill) exceeds `max_dispersion`, the filter is
+/// rebuilt from the log with [`rehash_with_seed`](BloomFilter::rehash_with_seed). An adversary
+/// who crafted colliding keys against the old seed loses their advantage.
+///
+/// The new seed is drawn from the thread-local random generator, which is seeded by the
+/// operating system. Deriving it from the old seed would let the same adversary compute the
+/// next seed and craft collisions against it in turn.
+///
+/// The key log costs memory proportional to the number of inserted keys.
+pub struct AdaptiveBloomFilter<T, H: Hasher64> {
+    filter: BloomFilter<T, H>,
+    log: KeyLog<T>,
+    check_interval: usize,
+    max_dispersion: f64,
+    since_check: usize,
+    rehashes: usize,
+}
+
+impl<T: Hash + Clone, H: Hasher64> AdaptiveBloomFilter<T, H> {
+    /// Creates a filter checking for clustering every 256 inserts, with a dispersion threshold
+    /// of 4.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        Self::with_policy(
+            capacity,
+            false_positive_rate,
+            DEFAULT_CHECK_INTERVAL,
+            DEFAULT_MAX_DISPERSION,
+        )
+    }
+
+    pub fn with_policy(
+        capacity: usize,
+        false_positive_rate: f64,
+        check_interval: usize,
+        max_dispersion: f64,
+    ) -> Self {
+        assert!(check_interval > 0, "Check interval must be greater than 0");
+        AdaptiveBloomFilter {
+            filter: BloomFilter::new(capacity, false_positive_rate),
+            log: KeyLog::new(),
+            check_interval,
+            max_dispersion,
+            since_check: 0,
+            rehashes: 0,
+        }
+    }
+
+    /// Dispersion index of the current fill: observed variance of the per-bucket set-bit counts
+    /// divided by the variance expected from uniform hashing.
+    pub fn dispersion(&self) -> f64 {
+        let buckets = HISTOGRAM_BUCKETS.min(self.filter.num_bits());
+        let m = self.filter.num_bits();
+        let p = self.filter.fill_ratio();
+        if p == 0.0 || p == 1.0 {
+            return 1.0;
+        }
+        let (observed, expected) = self
+            .filter
+            .fill_histogram(buckets)
+            .into_iter()
+            .enumerate()
+            .map(|(bucket, count)| {
+                let size = ((bucket + 1) * m / buckets - bucket * m / buckets) as f64;
+                ((count as f64 - size * p).powi(2), size * p * (1.0 - p))
+            })
+            .fold((0.0, 0.0), |(obs, exp), (o, e)| (obs + o, exp + e));
+        observed / expected
+    }
+
+    /// Number of times the filter has been rehashed.
+    pub fn rehashes(&self) -> usize {
+        self.rehashes
+    }
+
+    /// The underlying filter.
+    pub fn filter(&self) -> &BloomFilter<T, H> {
+        &self.filter
+    }
+
+    fn check_clustering(&mut self) {
+        self.since_check = 0;
+        if self.dispersion() > self.max_dispersion {
+            let new_seed = rand::rng().random();
+            self.filter
+                .rehash_with_seed(new_seed, self.log.iter().cloned());
+            self.rehashes += 1;
+        }
+    }
+}
+
+impl<T: Hash + Clone, H: Hasher64> ApproximateMembershipQuery<T> for AdaptiveBloomFilter<T, H> {
+    fn insert(&mut self, item: &T) {
+        self.log.insert_into(&mut self.filter, item.clone());
+        self.since_check += 1;
+        if self.since_check >= self.check_interval {
+            self.check_clustering();
+        }
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        self.filter.contains(item)
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        self.filter.false_positive_rate()
+    }
+
+    fn theoretical_false_positive_rate(&self) -> f64 {
+        self.filter.theoretical_false_positive_rate()
+    }
+
+    fn capacity(&self) -> usize {
+        self.filter.capacity()
+    }
+
+    fn len(&self) -> usize {
+        self.filter.len()
+    }
+
+    fn clear(&mut self) {
+        self.filter.clear();
+        self.log.clear();
+        self.since_check = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::bloom::positions::double_hash_positions;
+    use crate::filters::bloom::standard::BaseHashers;
+    use crate::hashing::AHasher;
+
+    /// Finds keys whose every position lands in the first half of the bit array under the
+    /// filter's current seeds, as an adversary who knows the default seeds could.
+    fn adversarial_keys(filter: &BloomFilter<u64, AHasher>, count: usize) -> Vec<u64> {
+        let hashers = BaseHashers::<AHasher>::from_seeds(filter.base_seeds());
+        let m = filter.num_bits();
+        (0u64..)
+            .filter(|key| {
+                let (h1, h2) = hashers.hash_item(key);
+                double_hash_positions(h1, h2, filter.num_hashes(), m).all(|pos| pos < m / 2)
+            })
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn test_uniform_fill_does_not_rehash() {
+        let mut filter = AdaptiveBloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            filter.insert(&i.wrapping_mul(0x9E3779B97F4A7C15));
+        }
+        assert_eq!(filter.rehashes(), 0);
+        assert!(
+            filter.dispersion() < 2.0,
+            "dispersion {}",
+            filter.dispersion()
+        );
+    }
+
+    #[test]
+    fn test_adversarial_keys_trigger_rehash() {
+        let mut filter = AdaptiveBloomFilter::<u64, AHasher>::new(1000, 0.1);
+        let keys = adversarial_keys(filter.filter(), 1000);
+
+        for key in &keys {
+            filter.insert(key);
+        }
+
+        assert!(filter.rehashes() >= 1, "Clustering went undetected");
+        assert_ne!(filter.filter().seed(), 0);
+        assert!(
+            filter.dispersion() < DEFAULT_MAX_DISPERSION,
+            "Fill still clustered after rehash: {}",
+            filter.dispersion()
+        );
+        for key in &keys {
+            assert!(filter.contains(key), "False negative for {}", key);
+        }
+        assert_eq!(filter.len(), keys.len());
+    }
+
+    #[test]
+    fn test_rehash_seeds_are_unpredictable() {
+        let probe = AdaptiveBloomFilter::<u64, AHasher>::new(1000, 0.1);
+        let keys = adversarial_keys(probe.filter(), 1000);
+        let rehashed_seed = || {
+            let mut filter = AdaptiveBloomFilter::<u64, AHasher>::new(1000, 0.1);
+            for key in &keys {
+                filter.insert(key);
+            }
+            assert!(filter.rehashes() >= 1);
+            filter.filter().seed()
+        };
+        assert_ne!(
+            rehashed_seed(),
+            rehashed_seed(),
+            "Identical inputs must not rehash to the same seed"
+        );
+    }
+}
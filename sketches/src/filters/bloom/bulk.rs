@@ -0,0 +1,93 @@
+use super::BloomFilter;
+use crate::hashing::Hasher64;
+use std::hash::Hash;
+
+/// Builds a [`BloomFilter`] from many items at once, for insert-heavy loads on large filters.
+///
+/// Inserting into a filter much larger than the CPU caches touches `k` random cache lines per
+/// item. The builder only hashes items as they are added; [`finalize`](Self::finalize) then
+/// groups their bit positions by 32 KiB block of the bit array and sets them block by block,
+/// trading those cache misses for two sequential passes over the positions.
+///
+/// That only pays off once the bit array is well past the last-level cache: building a 10M-item
+/// filter at a 0.01% rate (24 MB of bits) is about 20% faster than sequential inserts in the
+/// `bloom_bulk_build` benchmark, while a 1M-item filter at 1% (1.2 MB) is built twice as fast
+/// by plain inserts. The result is bit-for-bit the filter sequential inserts would have built,
+/// at the cost of holding 16 bytes per item until `finalize`.
+pub struct BulkBuilder<T, H: Hasher64> {
+    filter: BloomFilter<T, H>,
+    hashes: Vec<(u64, u64)>,
+}
+
+impl<T: Hash, H: Hasher64> BulkBuilder<T, H> {
+    /// Starts building a filter equivalent to `BloomFilter::new(capacity, false_positive_rate)`.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        BulkBuilder {
+            filter: BloomFilter::new(capacity, false_positive_rate),
+            hashes: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Queues `item` for insertion.
+    pub fn add(&mut self, item: &T) {
+        self.hashes.push(self.filter.base_hashes(item));
+    }
+
+    /// Number of items queued so far.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Sets the bits of every queued item and returns the ready-to-query filter.
+    pub fn finalize(mut self) -> BloomFilter<T, H> {
+        self.filter.insert_hashes_blocked(&self.hashes);
+        self.filter
+    }
+}
+
+impl<T: Hash, H: Hasher64> Extend<T> for BulkBuilder<T, H> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.add(&item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::traits::ApproximateMembershipQuery;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_matches_sequential_inserts() {
+        // Several blocks of bits and several batches of items
+        let n = 300_000;
+        let mut sequential = BloomFilter::<u64, AHasher>::new(n, 0.01);
+        let mut builder = BulkBuilder::<u64, AHasher>::new(n, 0.01);
+        for i in 0..n as u64 {
+            sequential.insert(&(i * 7919));
+            builder.add(&(i * 7919));
+        }
+        assert_eq!(builder.len(), n);
+
+        let bulk = builder.finalize();
+        assert_eq!(bulk.as_bytes(), sequential.as_bytes());
+        assert_eq!(bulk.len(), sequential.len());
+        assert!((0..n as u64).all(|i| bulk.contains(&(i * 7919))));
+    }
+
+    #[test]
+    fn test_empty_builder() {
+        let mut builder = BulkBuilder::<u64, AHasher>::new(100, 0.01);
+        assert!(builder.is_empty());
+        builder.extend(0..0u64);
+        let filter = builder.finalize();
+        assert!(filter.is_empty());
+        assert_eq!(filter.set_bits(), 0);
+    }
+}
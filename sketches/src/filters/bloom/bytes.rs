@@ -0,0 +1,127 @@
+use super::BloomFilter;
+use crate::filters::error::DecodeError;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::io::{self, BufRead};
+
+/// A Bloom filter over raw byte-slice keys.
+///
+/// Keys are hashed directly with `H`, without the `Hash` pass and its 8-byte digest that
+/// [`BloomFilter`] applies to items of type `T`. This is the natural API for keys that already
+/// are bytes (serialized rows, protocol buffers, strings) and keeps every byte of the key
+/// visible to the hasher. It is a [`BloomFilter`] fed through
+/// [`insert_bytes`](BloomFilter::insert_bytes), so it shares that type's sizing and
+/// serialization format.
+pub struct ByteBloomFilter<H: Hasher64> {
+    filter: BloomFilter<Vec<u8>, H>,
+}
+
+impl<H: Hasher64> ByteBloomFilter<H> {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        ByteBloomFilter {
+            filter: BloomFilter::new(capacity, false_positive_rate),
+        }
+    }
+
+    pub fn insert(&mut self, key: &[u8]) {
+        self.filter.insert_bytes(key);
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.filter.contains_bytes(key)
+    }
+
+    /// Inserts every non-empty line of `reader`. See [`BloomFilter::ingest_lines`].
+    pub fn ingest_lines<R: BufRead>(&mut self, reader: R) -> io::Result<usize> {
+        self.filter.ingest_lines(reader)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.filter.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.filter.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.filter.clear();
+    }
+
+    pub fn false_positive_rate(&self) -> f64 {
+        self.filter.false_positive_rate()
+    }
+
+    /// See [`BloomFilter::current_false_positive_rate`].
+    pub fn current_false_positive_rate(&self) -> f64 {
+        self.filter.current_false_positive_rate()
+    }
+
+    pub fn num_bits(&self) -> usize {
+        self.filter.num_bits()
+    }
+
+    /// Serializes the filter in the [`BloomFilter::as_bytes`] format.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.filter.as_bytes()
+    }
+
+    /// Decodes a filter serialized by [`as_bytes`](Self::as_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(ByteBloomFilter {
+            filter: BloomFilter::from_bytes(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::XXHasher;
+
+    #[test]
+    fn test_string_keys_against_generic_filter() {
+        let keys: Vec<String> = (0..10_000)
+            .map(|i| format!("/var/log/service/{:08}/events.jsonl", i))
+            .collect();
+        let absent: Vec<String> = (10_000..110_000)
+            .map(|i| format!("/var/log/service/{:08}/events.jsonl", i))
+            .collect();
+
+        let mut bytes = ByteBloomFilter::<XXHasher>::new(keys.len(), 0.01);
+        let mut generic = BloomFilter::<String, XXHasher>::new(keys.len(), 0.01);
+        for key in &keys {
+            bytes.insert(key.as_bytes());
+            generic.insert(key);
+        }
+
+        assert!(keys.iter().all(|key| bytes.contains(key.as_bytes())));
+        assert_eq!(bytes.num_bits(), generic.num_bits());
+        let fpr = |contains: &dyn Fn(&String) -> bool| {
+            absent.iter().filter(|key| contains(key)).count() as f64 / absent.len() as f64
+        };
+        let bytes_fpr = fpr(&|key| bytes.contains(key.as_bytes()));
+        let generic_fpr = fpr(&|key| generic.contains(key));
+        assert!(bytes_fpr < 0.013, "byte-key FPR {}", bytes_fpr);
+        assert!(
+            bytes_fpr < generic_fpr * 1.2,
+            "byte-key FPR {} vs generic {}",
+            bytes_fpr,
+            generic_fpr
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut filter = ByteBloomFilter::<XXHasher>::new(100, 0.01);
+        filter.insert(b"alpha");
+        filter.insert(&[0, 255, 7]);
+        let decoded = ByteBloomFilter::<XXHasher>::from_bytes(&filter.as_bytes()).unwrap();
+        assert!(decoded.contains(b"alpha") && decoded.contains(&[0, 255, 7]));
+        assert_eq!(decoded.len(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use super::BloomFilter;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::{Hasher64, PrecomputedHashes};
+use std::hash::Hash;
+
+/// Marks a timestamp slot no item has been recorded in.
+const EMPTY: u32 = u32::MAX;
+
+/// A Bloom filter that also remembers roughly when each item was first seen.
+///
+/// Alongside the filter sits an array of coarse `u32` timestamps (e.g. seconds or minutes
+/// since some epoch), indexed by the item's first base hash. Items sharing a slot share a
+/// timestamp, and the slot keeps the earliest one, so a returned time is never later than the
+/// item's real first observation but can be earlier when an older item collided with it.
+/// More slots mean fewer collisions, at 4 bytes each.
+///
+/// Membership has the filter's false positive rate, except that an item whose slot is still
+/// empty is known to be new, so false positives only happen in filled slots.
+pub struct FirstSeenFilter<T, H: Hasher64> {
+    filter: BloomFilter<T, H>,
+    first_seen: Vec<u32>,
+}
+
+impl<T: Hash, H: Hasher64> FirstSeenFilter<T, H> {
+    /// Creates a filter for `capacity` items at `false_positive_rate`, with `slots` timestamps.
+    pub fn new(capacity: usize, false_positive_rate: f64, slots: usize) -> Self {
+        assert!(slots > 0, "Slots must be greater than 0");
+        FirstSeenFilter {
+            filter: BloomFilter::new(capacity, false_positive_rate),
+            first_seen: vec![EMPTY; slots],
+        }
+    }
+
+    /// Returns when `item` was (approximately) first seen, or records `now` for it and returns
+    /// `None` if it is new.
+    ///
+    /// `now` must be below `u32::MAX`, which marks empty slots.
+    pub fn observe(&mut self, item: &T, now: u32) -> Option<u32> {
+        assert!(now != EMPTY, "Timestamp must be below u32::MAX");
+        let hashes = self.hashes(item);
+        let slot = self.slot(&hashes);
+        if let Some(seen) = self.lookup(&hashes) {
+            return Some(seen);
+        }
+        self.filter.insert_precomputed(&hashes);
+        self.first_seen[slot] = self.first_seen[slot].min(now);
+        None
+    }
+
+    /// Returns when `item` was (approximately) first seen, without recording it.
+    pub fn first_seen(&self, item: &T) -> Option<u32> {
+        self.lookup(&self.hashes(item))
+    }
+
+    /// Number of new items recorded.
+    pub fn len(&self) -> usize {
+        self.filter.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    pub fn slots(&self) -> usize {
+        self.first_seen.len()
+    }
+
+    fn hashes(&self, item: &T) -> PrecomputedHashes {
+        let (first, second) = self.filter.base_hashes(item);
+        PrecomputedHashes { first, second }
+    }
+
+    fn lookup(&self, hashes: &PrecomputedHashes) -> Option<u32> {
+        let seen = self.first_seen[self.slot(hashes)];
+        (seen != EMPTY && self.filter.contains_precomputed(hashes)).then_some(seen)
+    }
+
+    /// Slot for an item, from the high bits of its first hash: the filter's positions only use
+    /// the low bits.
+    fn slot(&self, hashes: &PrecomputedHashes) -> usize {
+        ((hashes.first as u128 * self.first_seen.len() as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_reobserving_returns_first_time() {
+        let mut filter = FirstSeenFilter::<u64, AHasher>::new(10_000, 0.01, 40_000);
+        let false_positives = (0..10_000u64)
+            .filter(|&i| filter.observe(&i, i as u32).is_some())
+            .count();
+        assert!(false_positives < 50, "{} false positives", false_positives);
+        assert_eq!(filter.len(), 10_000 - false_positives);
+
+        let mut exact = 0;
+        for i in 0..10_000u64 {
+            let seen = filter.observe(&i, 50_000).expect("Seen item reported new");
+            assert!(
+                seen <= i as u32,
+                "Item {} reported first seen at {}",
+                i,
+                seen
+            );
+            exact += usize::from(seen == i as u32);
+        }
+        assert!(exact > 8000, "Only {} exact timestamps", exact);
+        assert_eq!(filter.first_seen(&0), Some(0));
+        assert_eq!(
+            filter.len(),
+            10_000 - false_positives,
+            "Repeats must not be recorded"
+        );
+    }
+
+    #[test]
+    fn test_unseen_items() {
+        let mut filter = FirstSeenFilter::<u64, AHasher>::new(1000, 0.01, 4000);
+        assert!(filter.is_empty());
+        assert_eq!(filter.first_seen(&1), None);
+        filter.observe(&1, 10);
+        let false_positives = (1000..11_000u64)
+            .filter(|i| filter.first_seen(i).is_some())
+            .count();
+        assert!(false_positives < 10, "{} false positives", false_positives);
+    }
+}
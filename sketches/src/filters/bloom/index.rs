@@ -0,0 +1,130 @@
+use super::ByteBloomFilter;
+use crate::hashing::Hasher64;
+use std::io::{self, Read, Write};
+
+/// An in-memory pre-check in front of a disk-resident key store, as LSM trees keep one per
+/// table file.
+///
+/// Before reading a key from disk, ask [`might_contain`](Self::might_contain): `false` means
+/// the key was never inserted and the read can be skipped, so lookups of absent keys only hit
+/// disk at the filter's false positive rate. `true` still needs the read. The index is saved
+/// next to the data it describes and loaded back when the store is opened.
+///
+/// Keys are the bytes the store itself keys by, hashed directly, so a saved index stays valid
+/// whichever toolchain the reading binary is built with (see
+/// [`BloomFilter::as_bytes`](super::BloomFilter::as_bytes) for why `Hash`-keyed filters don't).
+pub struct BloomIndex<H: Hasher64> {
+    filter: ByteBloomFilter<H>,
+}
+
+impl<H: Hasher64> BloomIndex<H> {
+    /// Creates an index for `capacity` keys at `false_positive_rate`.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        BloomIndex {
+            filter: ByteBloomFilter::new(capacity, false_positive_rate),
+        }
+    }
+
+    /// Records that `key` is stored.
+    pub fn insert(&mut self, key: &[u8]) {
+        self.filter.insert(key);
+    }
+
+    /// Returns false if `key` is definitely not stored, true if it may be.
+    pub fn might_contain(&self, key: &[u8]) -> bool {
+        self.filter.contains(key)
+    }
+
+    /// Number of keys inserted.
+    pub fn len(&self) -> usize {
+        self.filter.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    pub fn filter(&self) -> &ByteBloomFilter<H> {
+        &self.filter
+    }
+
+    /// Writes the index in the [`BloomFilter::as_bytes`](super::BloomFilter::as_bytes) format.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.filter.as_bytes())
+    }
+
+    /// Reads an index written by [`save`](Self::save), failing with
+    /// [`InvalidData`](io::ErrorKind::InvalidData) if it isn't a valid filter.
+    pub fn load<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(BloomIndex {
+            filter: ByteBloomFilter::from_bytes(&bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::XXHasher;
+
+    /// Hex of `BloomIndex::<XXHasher>::new(16, 0.01)` holding alice, bob, carol and dave.
+    const PINNED: &str = concat!(
+        "534b42460100",                             // magic, version, seed-derived hashers
+        "1e31ba847e8252c5",                         // hasher fingerprint
+        "9a00000000000000",                         // m
+        "0700000000000000",                         // k
+        "1000000000000000",                         // n
+        "7b14ae47e17a843f",                         // f
+        "0400000000000000",                         // count
+        "0000000000000000",                         // seed
+        "147c4a7fb979379e",                         // first seed offset
+        "4febd4273daeb2c2",                         // second seed offset
+        "00004000ab1c045400010000c402a01001400600", // bits
+    );
+
+    #[test]
+    fn test_save_and_load() {
+        let mut index = BloomIndex::<XXHasher>::new(1000, 0.01);
+        let keys: Vec<String> = (0..1000).map(|i| format!("user:{i}")).collect();
+        for key in &keys {
+            index.insert(key.as_bytes());
+        }
+        assert!(keys.iter().all(|key| index.might_contain(key.as_bytes())));
+
+        let mut saved = Vec::new();
+        index.save(&mut saved).unwrap();
+        let loaded = BloomIndex::<XXHasher>::load(saved.as_slice()).unwrap();
+        assert_eq!(loaded.len(), 1000);
+        assert!(keys.iter().all(|key| loaded.might_contain(key.as_bytes())));
+        let absent: Vec<String> = (1000..11_000).map(|i| format!("user:{i}")).collect();
+        assert!(
+            absent
+                .iter()
+                .all(|key| loaded.might_contain(key.as_bytes())
+                    == index.might_contain(key.as_bytes()))
+        );
+
+        saved.truncate(saved.len() - 1);
+        let err = BloomIndex::<XXHasher>::load(saved.as_slice())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_saved_bytes_are_pinned() {
+        let mut index = BloomIndex::<XXHasher>::new(16, 0.01);
+        for key in ["alice", "bob", "carol", "dave"] {
+            index.insert(key.as_bytes());
+        }
+        let mut saved = Vec::new();
+        index.save(&mut saved).unwrap();
+        // Changing these bytes breaks indexes already saved
+        let hex: String = saved.iter().map(|byte| format!("{byte:02x}")).collect();
+        assert_eq!(hex, PINNED);
+        let loaded = BloomIndex::<XXHasher>::load(saved.as_slice()).unwrap();
+        assert!(loaded.might_contain(b"carol"));
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(all(debug_assertions, feature = "debug-invariants"))]
+use std::collections::HashSet;
+
+/// Development check of the no-false-negatives invariant.
+///
+/// With the `debug-invariants` feature in a debug build, records the base hashes of every key
+/// inserted into a filter, and panics if a lookup of a recorded key comes back negative.
+/// Otherwise it is zero-sized and every method is a no-op, so release builds pay nothing.
+#[derive(Default)]
+pub(super) struct InsertLog {
+    #[cfg(all(debug_assertions, feature = "debug-invariants"))]
+    hashes: HashSet<(u64, u64)>,
+}
+
+impl InsertLog {
+    #[inline]
+    pub(super) fn record(&mut self, _h1: u64, _h2: u64) {
+        #[cfg(all(debug_assertions, feature = "debug-invariants"))]
+        self.hashes.insert((_h1, _h2));
+    }
+
+    /// Panics if `found` is false for a recorded key.
+    #[inline]
+    pub(super) fn check(&self, _h1: u64, _h2: u64, _found: bool) {
+        #[cfg(all(debug_assertions, feature = "debug-invariants"))]
+        assert!(
+            _found || !self.hashes.contains(&(_h1, _h2)),
+            "False negative: an inserted key was reported absent"
+        );
+    }
+
+    #[inline]
+    pub(super) fn clear(&mut self) {
+        #[cfg(all(debug_assertions, feature = "debug-invariants"))]
+        self.hashes.clear();
+    }
+}
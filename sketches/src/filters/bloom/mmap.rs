@@ -0,0 +1,188 @@
+use super::BloomFilter;
+use super::positions::double_hash_positions;
+use super::standard::{BaseHashers, HEADER_LEN, Header};
+use crate::filters::error::DecodeError;
+use crate::hashing::Hasher64;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::marker::PhantomData;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+/// A read-only Bloom filter answering queries directly against a memory-mapped file.
+///
+/// The file must contain the bytes produced by [`BloomFilter::as_bytes`]. The bit array is never
+/// copied into memory, so multi-gigabyte static filters can be shared across processes through
+/// the page cache. Only query methods are exposed.
+///
+/// The mapping is shared, so the filter sees later writes to the file. The file must not be
+/// truncated while it is mapped: a lookup touching a page past the new end of the file gets
+/// `SIGBUS`, which aborts the process, on the next [`contains`](Self::contains).
+pub struct ReadOnlyBloom<T, H: Hasher64> {
+    map: Mmap,
+    m: usize,
+    k: usize,
+    n: usize,
+    f: f64,
+    count: usize,
+    hashers: BaseHashers<H>,
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: Hash, H: Hasher64> BloomFilter<T, H> {
+    /// Memory-maps a file produced by [`as_bytes`](Self::as_bytes) for read-only querying.
+    ///
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the file is not a serialized filter
+    /// or its length differs from the one its header declares.
+    pub fn open_mmap(path: &Path) -> io::Result<ReadOnlyBloom<T, H>> {
+        let map = Mmap::open(path)?;
+        let header = Header::decode(map.as_slice())?;
+        header.validate()?;
+        let hashers = header.hashers()?;
+        if map.as_slice().len() - HEADER_LEN != header.m.div_ceil(8) {
+            return Err(DecodeError::LengthMismatch.into());
+        }
+        Ok(ReadOnlyBloom {
+            map,
+            m: header.m,
+            k: header.k,
+            n: header.n,
+            f: header.f,
+            count: header.count,
+            hashers,
+            _phantom_data: PhantomData,
+        })
+    }
+}
+
+impl<T: Hash, H: Hasher64> ReadOnlyBloom<T, H> {
+    pub fn contains(&self, item: &T) -> bool {
+        let bits = &self.map.as_slice()[HEADER_LEN..];
+        let (hash1, hash2) = self.hashers.hash_item(item);
+        double_hash_positions(hash1, hash2, self.k, self.m)
+            .all(|pos| bits[pos / 8] & (0x80 >> (pos % 8)) != 0)
+    }
+
+    pub fn false_positive_rate(&self) -> f64 {
+        self.f
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.n
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A shared, read-only mapping of an entire file.
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// SAFETY: the mapping is read-only and owned exclusively by this struct until it is unmapped.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "file too large to map"))?;
+        if len < HEADER_LEN {
+            return Err(DecodeError::BadHeader.into());
+        }
+        // SAFETY: we request a fresh read-only mapping of `len` bytes of a valid descriptor;
+        // the result is checked against MAP_FAILED before use.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap { ptr, len })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` readable bytes for as long as the mapping lives.
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        // SAFETY: `ptr`/`len` describe a mapping created by `Mmap::open` that is unmapped once.
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::traits::ApproximateMembershipQuery;
+    use crate::hashing::AHasher;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sketches-{}-{}.bloom", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_mmap_round_trip() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            bf.insert(&i);
+        }
+        let path = temp_path("round-trip");
+        std::fs::write(&path, bf.as_bytes()).unwrap();
+
+        let mapped = BloomFilter::<u64, AHasher>::open_mmap(&path).unwrap();
+        assert_eq!(mapped.len(), bf.len());
+        assert_eq!(mapped.capacity(), bf.capacity());
+        for i in 0..20_000u64 {
+            assert_eq!(mapped.contains(&i), bf.contains(&i), "Mismatch for {}", i);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_rejects_truncated_file() {
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let bytes = bf.as_bytes();
+        let path = temp_path("truncated");
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+
+        let err = BloomFilter::<u64, AHasher>::open_mmap(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_rejects_trailing_bytes() {
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let mut bytes = bf.as_bytes();
+        bytes.extend_from_slice(&bf.as_bytes());
+        let path = temp_path("concatenated");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let err = BloomFilter::<u64, AHasher>::open_mmap(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,3 +1,31 @@
+mod adaptive;
+mod bulk;
+mod bytes;
+mod first_seen;
+mod index;
+mod invariants;
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+mod overlay;
+mod positions;
+mod prefix;
 mod standard;
+mod storage;
+mod summary;
+mod windowed;
 
-pub use standard::BloomFilter;
+pub use adaptive::AdaptiveBloomFilter;
+pub use bulk::BulkBuilder;
+pub use bytes::ByteBloomFilter;
+pub use first_seen::FirstSeenFilter;
+pub use index::BloomIndex;
+#[cfg(all(feature = "mmap", unix))]
+pub use mmap::ReadOnlyBloom;
+pub use overlay::DeletableOverlay;
+pub(crate) use positions::double_hash_positions;
+pub use prefix::PrefixBloomFilter;
+pub(crate) use standard::{BaseHashers, DEFAULT_SEED_OFFSETS};
+pub use standard::{BloomFilter, HEADER_LEN, KRounding, MAX_BITS};
+pub use storage::{BitStorage, DenseBits};
+pub use summary::BloomSummary;
+pub use windowed::WindowedBloomFilter;
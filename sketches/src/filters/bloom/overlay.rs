@@ -0,0 +1,140 @@
+use super::BloomFilter;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::hash::Hash;
+
+/// Approximate deletion on top of an existing Bloom filter: a primary filter shadowed by a
+/// filter of deleted keys, sometimes called a shadow-delete Bloom filter.
+///
+/// The base filter is left untouched; deleted items are recorded in a second, usually much
+/// smaller, Bloom filter, and an item is reported present only if the base contains it and the
+/// deleted set doesn't.
+///
+/// The errors are asymmetric. A false positive of the deleted set hides an item that was never
+/// deleted (a *false deletion*), so unlike a plain Bloom filter this can report a present item
+/// as absent, at a rate bounded by the deleted set's false positive rate. In exchange, deleting
+/// never resurrects anything: false positives of the base can only shrink. A deleted item stays
+/// hidden for good, even if it was inserted into the base more than once.
+///
+/// When the deleted set fills up, or if the keys are still at hand, [`compact`](Self::compact)
+/// rebuilds the base without the deleted keys and starts over with an empty deleted set.
+pub struct DeletableOverlay<T, H: Hasher64> {
+    base: BloomFilter<T, H>,
+    deleted: BloomFilter<T, H>,
+}
+
+impl<T: Hash, H: Hasher64> DeletableOverlay<T, H> {
+    /// Wraps `base`, with room for about `max_deletions` deletions at `deletion_fpr` false
+    /// deletions.
+    pub fn new(base: BloomFilter<T, H>, max_deletions: usize, deletion_fpr: f64) -> Self {
+        DeletableOverlay {
+            base,
+            deleted: BloomFilter::new(max_deletions, deletion_fpr),
+        }
+    }
+
+    /// Hides `item` from subsequent lookups.
+    pub fn delete(&mut self, item: &T) {
+        self.deleted.insert(item);
+    }
+
+    /// Checks whether `item` is (probably) in the base filter and was not deleted.
+    pub fn contains(&self, item: &T) -> bool {
+        self.base.contains(item) && !self.deleted.contains(item)
+    }
+
+    /// Checks whether `item` was (probably) deleted.
+    pub fn is_deleted(&self, item: &T) -> bool {
+        self.deleted.contains(item)
+    }
+
+    /// Number of deletions recorded.
+    pub fn deletions(&self) -> usize {
+        self.deleted.len()
+    }
+
+    pub fn base(&self) -> &BloomFilter<T, H> {
+        &self.base
+    }
+
+    /// Returns the base filter, discarding the deletions.
+    pub fn into_base(self) -> BloomFilter<T, H> {
+        self.base
+    }
+
+    /// Rebuilds the base from `keys` without the deleted ones and empties the deleted set.
+    ///
+    /// Deleted keys are then gone from the base, only reappearing as its ordinary false
+    /// positives. Keys the deleted set falsely reports deleted are skipped too, so compaction
+    /// makes past false deletions permanent; only emptying the deleted set stops new ones from
+    /// happening. `keys` must be every key in the base, as for
+    /// [`rebuild_retaining`](BloomFilter::rebuild_retaining), which sizes the new base for the
+    /// keys kept.
+    pub fn compact(&mut self, keys: impl IntoIterator<Item = T>)
+    where
+        H: Clone,
+    {
+        let deleted = &self.deleted;
+        self.base = self
+            .base
+            .rebuild_retaining(keys.into_iter(), |key| !deleted.contains(key));
+        self.deleted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_delete_hides_item() {
+        let mut base = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            base.insert(&i);
+        }
+        let mut overlay = DeletableOverlay::new(base, 500, 0.001);
+        for i in 0..500u64 {
+            overlay.delete(&i);
+        }
+
+        for i in 0..500u64 {
+            assert!(!overlay.contains(&i), "Deleted item {} still present", i);
+            assert!(overlay.is_deleted(&i));
+        }
+        let false_deletions = (500..10_000u64).filter(|i| !overlay.contains(i)).count();
+        assert!(
+            false_deletions < 20,
+            "{} items falsely deleted",
+            false_deletions
+        );
+        assert_eq!(overlay.deletions(), 500);
+        assert!(overlay.base().contains(&0), "Base must be untouched");
+    }
+
+    #[test]
+    fn test_compact_drops_deleted_keys() {
+        let mut base = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            base.insert(&i);
+        }
+        let mut overlay = DeletableOverlay::new(base, 1000, 0.01);
+        for i in 0..1000u64 {
+            overlay.delete(&i);
+        }
+        assert!(!overlay.contains(&0));
+        // Keys the deleted set falsely reports are dropped along with the deleted ones
+        let kept: Vec<u64> = (1000..10_000u64).filter(|i| overlay.contains(i)).collect();
+        assert!(kept.len() > 8800);
+
+        overlay.compact(0..10_000u64);
+        assert_eq!(overlay.deletions(), 0);
+        assert_eq!(overlay.base().len(), kept.len());
+        let resurrected = (0..1000u64).filter(|i| overlay.contains(i)).count();
+        assert!(resurrected < 30, "{} deleted keys back", resurrected);
+        assert!(
+            kept.iter().all(|i| overlay.contains(i)),
+            "Compaction must keep every key it retained"
+        );
+    }
+}
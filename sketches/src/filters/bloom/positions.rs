@@ -0,0 +1,169 @@
+/// Number of positions computed together by the lane kernel.
+const LANES: usize = 8;
+
+/// Smallest `k` for which positions are computed a lane at a time. Below this, a lane would
+/// mostly compute positions a short-circuiting lookup never reads.
+const LANE_THRESHOLD: usize = 2 * LANES;
+
+/// Generates k positions in `[0, m)` using only arithmetic on the two base hash values.
+///
+/// Double hashing: `h_i(x) = (h1(x) + i*h2(x)) mod m`, computed on the low 32 bits of each hash.
+/// For `k >= 16` the positions are produced eight at a time by a kernel over fixed-size arrays
+/// that the compiler can vectorize on stable Rust (`std::simd` is nightly-only). Both paths
+/// yield identical positions, and neither uses a hardware division.
+pub(crate) fn double_hash_positions(hash1: u64, hash2: u64, k: usize, m: usize) -> Positions {
+    Positions {
+        hash1: hash1 as u32,
+        hash2: hash2 as u32,
+        k,
+        reducer: Reducer::new(m),
+        next: 0,
+        lane: [0; LANES],
+        lane_start: 0,
+        lane_len: 0,
+    }
+}
+
+/// Iterator over the positions of an item. See [`double_hash_positions`].
+pub(crate) struct Positions {
+    hash1: u32,
+    hash2: u32,
+    k: usize,
+    reducer: Reducer,
+    next: usize,
+    lane: [usize; LANES], // Buffered positions `lane_start..lane_start + lane_len`
+    lane_start: usize,
+    lane_len: usize,
+}
+
+impl Positions {
+    fn scalar(&self, i: usize) -> usize {
+        let combined = self.hash1.wrapping_add((i as u32).wrapping_mul(self.hash2));
+        self.reducer.reduce(combined)
+    }
+
+    fn fill_lane(&mut self, start: usize) {
+        let mut combined = [0u32; LANES];
+        for (lane, value) in combined.iter_mut().enumerate() {
+            let i = (start + lane) as u32;
+            *value = self.hash1.wrapping_add(i.wrapping_mul(self.hash2));
+        }
+        for (slot, value) in self.lane.iter_mut().zip(combined) {
+            *slot = self.reducer.reduce(value);
+        }
+        self.lane_start = start;
+        self.lane_len = LANES.min(self.k - start);
+    }
+}
+
+impl Iterator for Positions {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next >= self.k {
+            return None;
+        }
+        let i = self.next;
+        self.next += 1;
+        if self.k < LANE_THRESHOLD {
+            return Some(self.scalar(i));
+        }
+        if i >= self.lane_start + self.lane_len {
+            self.fill_lane(i);
+        }
+        Some(self.lane[i - self.lane_start])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.k - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Positions {}
+
+/// Reduces 32-bit values modulo a fixed `m` without a hardware division.
+///
+/// Uses Lemire's direct remainder computation (`((c * x) mod 2^64) * m >> 64` with
+/// `c = ceil(2^64 / m)`), which is exact for every 32-bit `x` and `m`.
+#[derive(Clone, Copy)]
+struct Reducer {
+    m: u64,
+    magic: u64,
+}
+
+impl Reducer {
+    fn new(m: usize) -> Self {
+        assert!(m > 0, "Number of bits must be greater than 0");
+        let m = m as u64;
+        Reducer {
+            m,
+            magic: (u64::MAX / m).wrapping_add(1),
+        }
+    }
+
+    fn reduce(&self, x: u32) -> usize {
+        if self.m > u32::MAX as u64 {
+            // Every 32-bit value is already in range
+            return x as usize;
+        }
+        let low = self.magic.wrapping_mul(x as u64);
+        ((low as u128 * self.m as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// The textbook formula every path must agree with.
+    fn reference(hash1: u64, hash2: u64, k: usize, m: usize) -> Vec<usize> {
+        let (hash1, hash2) = (hash1 as u32, hash2 as u32);
+        (0..k)
+            .map(|i| (hash1.wrapping_add((i as u32).wrapping_mul(hash2)) as usize) % m)
+            .collect()
+    }
+
+    #[test]
+    fn test_lane_and_scalar_paths_match_reference() {
+        let mut rng = rand::rng();
+        let ms = [
+            1,
+            2,
+            3,
+            7,
+            64,
+            1000,
+            95_851,
+            1 << 31,
+            u32::MAX as usize,
+            1 << 33,
+        ];
+        for _ in 0..2000 {
+            let (hash1, hash2): (u64, u64) = (rng.random(), rng.random());
+            let m = ms[rng.random_range(0..ms.len())];
+            for k in [1, 7, LANE_THRESHOLD - 1, LANE_THRESHOLD, 17, 33, 64] {
+                assert_eq!(
+                    double_hash_positions(hash1, hash2, k, m).collect::<Vec<_>>(),
+                    reference(hash1, hash2, k, m),
+                    "hash1={} hash2={} k={} m={}",
+                    hash1,
+                    hash2,
+                    k,
+                    m
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reducer_edge_values() {
+        for m in [1, 2, 3, 1 << 16, (u32::MAX - 1) as usize, u32::MAX as usize] {
+            let reducer = Reducer::new(m);
+            for x in [0, 1, m as u32 - 1, m as u32, u32::MAX - 1, u32::MAX] {
+                assert_eq!(reducer.reduce(x), x as usize % m, "x={} m={}", x, m);
+            }
+        }
+    }
+}
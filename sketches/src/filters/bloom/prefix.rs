@@ -0,0 +1,133 @@
+use super::ByteBloomFilter;
+use crate::hashing::Hasher64;
+
+/// A Bloom filter over byte keys that can also answer whether any key with a given prefix was
+/// inserted.
+///
+/// Inserting a key also inserts its prefixes of each configured length, so
+/// [`contains_prefix`](Self::contains_prefix) is a single lookup that can skip range scans
+/// (e.g. an IP range or a block of sorted ids) when it returns `false`. Only the configured
+/// lengths are indexed: querying a prefix of any other length finds nothing unless it happens
+/// to equal a whole inserted key.
+///
+/// Every key costs up to one insertion per configured prefix length on top of its own, so the
+/// filter is sized for `capacity * (1 + prefix_lengths.len())` entries; memory grows by that
+/// factor compared to a plain filter for the same keys and false positive rate.
+pub struct PrefixBloomFilter<H: Hasher64> {
+    filter: ByteBloomFilter<H>,
+    prefix_lengths: Vec<usize>,
+    keys: usize,
+}
+
+impl<H: Hasher64> PrefixBloomFilter<H> {
+    /// Creates a filter for `capacity` keys indexing prefixes of each of `prefix_lengths` bytes.
+    pub fn new(capacity: usize, false_positive_rate: f64, prefix_lengths: &[usize]) -> Self {
+        assert!(
+            prefix_lengths.iter().all(|&len| len > 0),
+            "Prefix lengths must be greater than 0"
+        );
+        let mut prefix_lengths = prefix_lengths.to_vec();
+        prefix_lengths.sort_unstable();
+        prefix_lengths.dedup();
+        PrefixBloomFilter {
+            filter: ByteBloomFilter::new(
+                capacity * (1 + prefix_lengths.len()),
+                false_positive_rate,
+            ),
+            prefix_lengths,
+            keys: 0,
+        }
+    }
+
+    /// Inserts `key` and its prefixes of every configured length shorter than the key.
+    pub fn insert(&mut self, key: &[u8]) {
+        for &len in self
+            .prefix_lengths
+            .iter()
+            .take_while(|&&len| len < key.len())
+        {
+            self.filter.insert(&key[..len]);
+        }
+        self.filter.insert(key);
+        self.keys += 1;
+    }
+
+    /// Checks whether `key` was (probably) inserted.
+    ///
+    /// A configured-length prefix of an inserted key is indistinguishable from a key, so it is
+    /// reported present as well.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.filter.contains(key)
+    }
+
+    /// Checks whether any inserted key (probably) starts with `prefix`.
+    ///
+    /// Never a false negative when `prefix.len()` is one of the configured lengths.
+    pub fn contains_prefix(&self, prefix: &[u8]) -> bool {
+        self.filter.contains(prefix)
+    }
+
+    pub fn prefix_lengths(&self) -> &[usize] {
+        &self.prefix_lengths
+    }
+
+    /// Number of keys inserted, not counting their prefixes.
+    pub fn len(&self) -> usize {
+        self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys == 0
+    }
+
+    /// Number of bits used, including the prefix entries.
+    pub fn num_bits(&self) -> usize {
+        self.filter.num_bits()
+    }
+
+    pub fn false_positive_rate(&self) -> f64 {
+        self.filter.false_positive_rate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_prefixes_of_inserted_keys_are_present() {
+        let mut filter = PrefixBloomFilter::<AHasher>::new(1000, 0.01, &[2, 3]);
+        // IPv4 addresses in 10.1.0.0/16
+        let keys: Vec<[u8; 4]> = (0..1000u32)
+            .map(|i| [10, 1, (i / 256) as u8, (i % 256) as u8])
+            .collect();
+        for key in &keys {
+            filter.insert(key);
+        }
+
+        assert_eq!(filter.len(), 1000);
+        assert!(filter.contains_prefix(&[10, 1]));
+        for key in &keys {
+            assert!(filter.contains(key));
+            assert!(filter.contains_prefix(&key[..3]));
+        }
+
+        let unrelated = (0..=255u8)
+            .flat_map(|a| (0..=255u8).map(move |b| [a, b]))
+            .filter(|prefix| prefix != &[10, 1])
+            .filter(|prefix| filter.contains_prefix(prefix))
+            .count();
+        assert!(unrelated < 1000, "{} unrelated /16 prefixes", unrelated);
+        assert!(!filter.contains_prefix(&[192, 168, 0]));
+    }
+
+    #[test]
+    fn test_short_keys_skip_longer_prefixes() {
+        let mut filter = PrefixBloomFilter::<AHasher>::new(10, 0.01, &[4, 2]);
+        assert_eq!(filter.prefix_lengths(), &[2, 4]);
+        filter.insert(b"abc");
+        assert!(filter.contains(b"abc"));
+        assert!(filter.contains_prefix(b"ab"));
+    }
+}
@@ -1,106 +1,1115 @@
+use super::invariants::InsertLog;
+use super::positions::double_hash_positions;
+use super::storage::BitStorage;
+use crate::filters::error::{DecodeError, MergeError, Saturated, SizingError};
 use crate::filters::traits::ApproximateMembershipQuery;
-use crate::hashing::Hasher64;
+use crate::hashing::{Hasher64, PrecomputedHashes, hash_item, item_bytes};
+use crate::similarity::SimilarityEstimator;
 use bit_vec::BitVec;
 use std::hash::Hash;
+use std::io::{self, BufRead};
 use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Magic bytes identifying a serialized [`BloomFilter`].
+const MAGIC: &[u8; 4] = b"SKBF";
+
+/// Version of the serialization format, bumped whenever the layout changes.
+const FORMAT_VERSION: u8 = 1;
+
+/// Length of the fixed serialization header: magic, a format version byte, a byte telling
+/// whether the hashers were [seed-derived](BloomFilter::new) or
+/// [an instance](BloomFilter::with_hasher_instance), a fingerprint of the
+/// [`hasher_id`](BloomFilter::hasher_id), and then `m`, `k`, `n`, `f`, `count`, `seed` and the
+/// two seed offsets. The fingerprint and the parameters are 8 little-endian bytes each.
+pub const HEADER_LEN: usize = MAGIC.len() + 2 + 8 + 8 * 8;
+
+/// Offsets added to the seed to get the seeds of the two base hashers.
+///
+/// Seeds `s` and `s + 1` differ in a single bit, and hashers with weak seed mixing then
+/// produce correlated base hashes. These are far apart in every bit position instead. One is
+/// even and one odd, so a [`Combined`](crate::hashing::Combined) hasher still computes one
+/// base hash with each of its halves.
+pub(crate) const DEFAULT_SEED_OFFSETS: [u64; 2] = [0x9E37_79B9_7F4A_7C14, 0xC2B2_AE3D_27D4_EB4F];
+
+/// Largest number of bits a filter may have, so that its bit array's size in bytes can't
+/// overflow. Filters far smaller than this can still exceed the memory available.
+pub const MAX_BITS: usize = isize::MAX as usize;
+
+/// Largest `k` a decoded header may declare. Reaching it would take a false positive rate
+/// below the smallest positive `f64`, so it only rejects corrupt input, which could otherwise
+/// make every lookup loop for an arbitrarily long time.
+const MAX_DECODED_HASHES: usize = 2048;
+
+/// How the optimal, fractional number of hash functions `(m / n) * ln 2` is turned into `k`.
+///
+/// The false positive rate is minimized at the fractional optimum and rises on either side of
+/// it, so rounding to the nearest integer usually lands closer to the configured rate; always
+/// rounding up can overshoot it. The difference is a few percent of the rate at small `k` (e.g.
+/// `1.4` becoming `2` instead of `1`) and negligible at large `k`. Rounding down also saves a
+/// position computation and bit probe per operation whenever the two strategies disagree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KRounding {
+    /// Round to the nearest integer (the default).
+    #[default]
+    Nearest,
+    /// Always round up.
+    Ceil,
+}
 
 /// A standard Bloom filter implementation.
 /// Uses a single contiguous bit array and double hashing for generating multiple hash functions.
+/// The bit array is pluggable through [`BitStorage`] and defaults to a [`BitVec`].
 ///
-pub struct BloomFilter<T, H: Hasher64> {
-    bit_array: BitVec,
+pub struct BloomFilter<T, H: Hasher64, S: BitStorage = BitVec> {
+    bit_array: S,
     m: usize,     // Number of bits
     k: usize,     // Number of hash functions
     n: usize,     // Expected number of elements
     f: f64,       // Configured false positive rate
     count: usize, // Actual number of inserted items
+    seed: u64,    // Base seed for the two hash functions
+    seed_offsets: [u64; 2],
+    hashers: BaseHashers<H>,
+    inserted: InsertLog, // Checks lookups of inserted keys with the `debug-invariants` feature
     _phantom_data: PhantomData<T>,
-    _phantom_hasher: PhantomData<H>,
 }
 
-impl<T, H: Hasher64> BloomFilter<T, H> {
+impl<T, H: Hasher64, S: BitStorage> BloomFilter<T, H, S> {
     pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        Self::with_k_rounding(capacity, false_positive_rate, KRounding::default())
+    }
+
+    /// Creates a filter like [`new`](Self::new), but returns an error instead of panicking when
+    /// the parameters are invalid or the filter would need more than [`MAX_BITS`] bits (e.g. a
+    /// capacity near `usize::MAX`, or a vanishingly small false positive rate).
+    pub fn try_new(capacity: usize, false_positive_rate: f64) -> Result<Self, SizingError> {
+        let rate_valid = false_positive_rate > 0.0 && false_positive_rate < 1.0;
+        if capacity == 0 || !rate_valid {
+            return Err(SizingError::InvalidParameters);
+        }
+        let m = Self::calculate_m(capacity, false_positive_rate)?;
+        let k = Self::calculate_k(m, capacity, KRounding::default());
+        Ok(Self::with_dimensions(
+            capacity,
+            false_positive_rate,
+            m,
+            k,
+            0,
+            DEFAULT_SEED_OFFSETS,
+            BaseHashers::from_seeds(DEFAULT_SEED_OFFSETS),
+        ))
+    }
+
+    /// Creates a filter whose number of hash functions is rounded with `rounding`.
+    pub fn with_k_rounding(capacity: usize, false_positive_rate: f64, rounding: KRounding) -> Self {
+        Self::with_hashers(
+            capacity,
+            false_positive_rate,
+            rounding,
+            0,
+            DEFAULT_SEED_OFFSETS,
+            BaseHashers::from_seeds(DEFAULT_SEED_OFFSETS),
+        )
+    }
+
+    /// Creates a filter whose two base hashers are seeded with `seed + seed_offsets[0]` and
+    /// `seed + seed_offsets[1]` instead of the default, well-separated offsets.
+    ///
+    /// The offsets are kept across [`rehash_with_seed`](Self::rehash_with_seed) and recorded
+    /// by [`as_bytes`](Self::as_bytes).
+    pub fn with_seed_offsets(
+        capacity: usize,
+        false_positive_rate: f64,
+        seed_offsets: [u64; 2],
+    ) -> Self {
+        assert!(
+            seed_offsets[0] != seed_offsets[1],
+            "Seed offsets must differ"
+        );
+        Self::with_hashers(
+            capacity,
+            false_positive_rate,
+            KRounding::default(),
+            0,
+            seed_offsets,
+            BaseHashers::from_seeds(seed_offsets),
+        )
+    }
+
+    /// Creates a filter that hashes with an already-configured hasher instance.
+    ///
+    /// The instance computes the first base hash as-is, so a keyed hasher (e.g. an `AHasher`
+    /// with specific keys) keeps its keys. The second base hash comes from a companion hasher
+    /// seeded from the instance's own output, so filters built from clones of the same instance
+    /// map every item to the same bits.
+    ///
+    /// The instance isn't recorded by [`as_bytes`](Self::as_bytes), so
+    /// [`from_bytes`](Self::from_bytes) rejects the result with [`DecodeError::InstanceHasher`]
+    /// rather than decoding it with hashers that map items elsewhere.
+    /// [`rehash_with_seed`](Self::rehash_with_seed) replaces it with seed-derived hashers,
+    /// after which the filter can be decoded again.
+    pub fn with_hasher_instance(capacity: usize, false_positive_rate: f64, hasher: H) -> Self {
+        Self::with_hashers(
+            capacity,
+            false_positive_rate,
+            KRounding::default(),
+            0,
+            DEFAULT_SEED_OFFSETS,
+            BaseHashers::from_instance(hasher),
+        )
+    }
+
+    /// Creates a filter with exactly `k` hash functions and the fewest bits that keep its
+    /// false positive rate at `capacity` items within `false_positive_rate`.
+    ///
+    /// Solves `f = (1 - e^(-k * n / m))^k` for `m`, giving `m = -k * n / ln(1 - f^(1/k))`.
+    /// Pinning `k` bounds the memory accesses per operation, at the cost of more bits than the
+    /// optimal `k` would need for the same rate. Panics unless the rate is within `(0, 1)`.
+    pub fn with_fixed_k(capacity: usize, false_positive_rate: f64, k: usize) -> Self {
         assert!(capacity > 0, "Capacity must be greater than 0");
-        let m = Self::calculate_m(capacity, false_positive_rate);
-        let k = Self::calculate_k(m, capacity);
+        assert!(k > 0, "Number of hash functions must be greater than 0");
+        let m = Self::calculate_m_for_k(capacity, false_positive_rate, k)
+            .unwrap_or_else(|err| panic!("Cannot size filter: {}", err));
+        Self::with_dimensions(
+            capacity,
+            false_positive_rate,
+            m,
+            k,
+            0,
+            DEFAULT_SEED_OFFSETS,
+            BaseHashers::from_seeds(DEFAULT_SEED_OFFSETS),
+        )
+    }
+
+    fn with_hashers(
+        capacity: usize,
+        false_positive_rate: f64,
+        rounding: KRounding,
+        seed: u64,
+        seed_offsets: [u64; 2],
+        hashers: BaseHashers<H>,
+    ) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+        let m = Self::calculate_m(capacity, false_positive_rate)
+            .unwrap_or_else(|err| panic!("Cannot size filter: {}", err));
+        let k = Self::calculate_k(m, capacity, rounding);
+        Self::with_dimensions(
+            capacity,
+            false_positive_rate,
+            m,
+            k,
+            seed,
+            seed_offsets,
+            hashers,
+        )
+    }
+
+    fn with_dimensions(
+        capacity: usize,
+        false_positive_rate: f64,
+        m: usize,
+        k: usize,
+        seed: u64,
+        seed_offsets: [u64; 2],
+        hashers: BaseHashers<H>,
+    ) -> Self {
         BloomFilter {
-            bit_array: BitVec::from_elem(m, false),
+            bit_array: S::with_len(m),
             m,
             k,
             n: capacity,
             f: false_positive_rate,
             count: 0,
+            seed,
+            seed_offsets,
+            hashers,
+            inserted: InsertLog::default(),
             _phantom_data: PhantomData,
-            _phantom_hasher: PhantomData,
         }
     }
 
-    fn calculate_m(n: usize, f: f64) -> usize {
-        (-(n as f64) * f.ln() / (2f64.ln().powi(2))).ceil() as usize
+    /// Identifies the hash algorithm. Static hasher types always agree; this distinguishes
+    /// filters over [`Box<dyn DynHasher64>`](crate::hashing::DynHasher64).
+    pub fn hasher_id(&self) -> &'static str {
+        self.hashers.first.hasher_id()
+    }
+
+    /// Returns the base seed the two hash functions are derived from.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
-    fn calculate_k(m: usize, n: usize) -> usize {
-        ((m as f64 / n as f64) * 2f64.ln()).ceil() as usize
+    /// Returns the offsets added to [`seed`](Self::seed) to seed the two base hashers.
+    pub fn seed_offsets(&self) -> [u64; 2] {
+        self.seed_offsets
     }
 
-    /// Generates k hash positions for an item using double hashing technique.
+    /// Returns the seeds of the two base hashers: the seed plus each offset.
+    pub fn base_seeds(&self) -> [u64; 2] {
+        base_seeds(self.seed, self.seed_offsets)
+    }
+
+    /// Moves the filter behind an [`Arc`] to share it read-only between threads.
     ///
-    /// This method computes only 2 actual hash values and then uses arithmetic operations
-    /// to simulate k independent hash functions. This is significantly more efficient than
-    /// computing k separate hashes.
+    /// Lookups only read the bits and the hashers, without interior mutability, so the filter
+    /// is `Sync` whenever its hasher and storage are (every hasher in this crate is, boxed
+    /// [`DynHasher64`](crate::hashing::DynHasher64)s included), and
+    /// [`contains`](ApproximateMembershipQuery::contains) can be called from any number of
+    /// threads at once without locking.
+    pub fn into_shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Rebuilds the filter under a new hasher seed.
     ///
-    /// # Double Hashing Formula
-    /// For each i in 0..k: `h_i(x) = (h1(x) + i * h2(x)) mod m`
+    /// All bits are cleared and every key yielded by `keys` is re-inserted using hash functions
+    /// derived from `new_seed`. Rotating the seed periodically stops an adversary from reusing
+    /// collisions learned against the previous seed.
     ///
-    /// # Performance
-    /// - Cost: 2 hash computations + k arithmetic operations
-    /// - Alternative cost: k hash computations
-    /// - Arithmetic operations (add, multiply, modulo) are orders of magnitude faster than hashing
-    fn hash_positions(&self, item: &T) -> impl Iterator<Item = usize> + '_
+    /// A Bloom filter cannot enumerate its contents, so the caller must supply the original keys
+    /// (e.g. from a key log). Keys that are not supplied are dropped from the rebuilt filter.
+    pub fn rehash_with_seed(&mut self, new_seed: u64, keys: impl Iterator<Item = T>)
     where
         T: Hash,
     {
-        // Compute two base hash values (this is where the actual hashing happens)
-        let hash1 = H::hash_with_seed(&self.to_bytes(item), 0) as u32;
-        let hash2 = H::hash_with_seed(&self.to_bytes(item), 1) as u32;
-
-        // Generate k positions using only arithmetic on the two hash values
-        // Double hashing: h_i(x) = (h1(x) + i*h2(x)) mod m
-        (0..self.k).map(move |i| {
-            let combined = hash1.wrapping_add((i as u32).wrapping_mul(hash2));
-            (combined as usize) % self.m
-        })
+        self.bit_array.clear();
+        self.inserted.clear();
+        self.count = 0;
+        self.seed = new_seed;
+        self.hashers = self.hashers.reseeded(self.base_seeds());
+        for key in keys {
+            self.insert(&key);
+        }
     }
 
-    fn to_bytes(&self, item: &T) -> Vec<u8>
+    /// Builds a fresh filter from `keys`, keeping only those for which `pred` is true.
+    ///
+    /// This is the compaction path for a filter whose keys are logged elsewhere: bits can't be
+    /// unset, so dropping keys means rebuilding from the ones to keep. The result is sized for
+    /// the number of retained keys at the same target rate, and hashes exactly like `self`
+    /// (same hashers and seeds). `keys` should be the filter's full key log; keys missing from
+    /// it are dropped as well.
+    pub fn rebuild_retaining(
+        &self,
+        keys: impl Iterator<Item = T>,
+        pred: impl Fn(&T) -> bool,
+    ) -> Self
     where
         T: Hash,
+        H: Clone,
     {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::Hasher as StdHasher;
-        let mut hasher = DefaultHasher::new();
-        item.hash(&mut hasher);
-        hasher.finish().to_le_bytes().to_vec()
+        let retained: Vec<T> = keys.filter(|key| pred(key)).collect();
+        let capacity = retained.len().max(1);
+        let m = Self::calculate_m(capacity, self.f)
+            .expect("A smaller capacity at the same rate always fits");
+        let mut rebuilt = Self::with_dimensions(
+            capacity,
+            self.f,
+            m,
+            Self::calculate_k(m, capacity, KRounding::default()),
+            self.seed,
+            self.seed_offsets,
+            self.hashers.clone(),
+        );
+        for key in &retained {
+            rebuilt.insert(key);
+        }
+        rebuilt
     }
-}
 
-impl<T: Hash, H: Hasher64> ApproximateMembershipQuery<T> for BloomFilter<T, H> {
-    fn insert(&mut self, item: &T) {
-        let positions: Vec<usize> = self.hash_positions(item).collect();
+    /// Inserts `item` only if doing so keeps the
+    /// [current false positive rate](Self::current_false_positive_rate) within the configured
+    /// target.
+    ///
+    /// Returns [`Saturated`] without modifying the filter otherwise, so callers can rotate to a
+    /// fresh filter instead of silently degrading past capacity.
+    pub fn try_insert(&mut self, item: &T) -> Result<(), Saturated>
+    where
+        T: Hash,
+    {
+        let (h1, h2) = self.hashers.hash_item(item);
+        let mut positions: Vec<usize> = double_hash_positions(h1, h2, self.k, self.m).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        let new_bits = positions
+            .iter()
+            .filter(|&&pos| !self.bit_array.get(pos))
+            .count();
+        let fill_after = (self.set_bits() + new_bits) as f64 / self.m as f64;
+        if fill_after.powi(self.k as i32) > self.f {
+            return Err(Saturated);
+        }
+
         for pos in positions {
             self.bit_array.set(pos, true);
         }
+        self.inserted.record(h1, h2);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Inserts an item given its two precomputed base hashes, skipping hashing entirely.
+    ///
+    /// Only the double-hashing position arithmetic runs, which lets callers hash an item once
+    /// and share the result across several sketches. The caller is responsible for hash
+    /// quality: `h1` and `h2` should be independent and well-distributed in their low 32 bits
+    /// (the only bits used), and the same pair must be passed to
+    /// [`contains_hash`](Self::contains_hash) for lookups.
+    pub fn insert_hash(&mut self, h1: u64, h2: u64) {
+        for pos in double_hash_positions(h1, h2, self.k, self.m) {
+            self.bit_array.set(pos, true);
+        }
+        self.inserted.record(h1, h2);
         self.count += 1;
     }
 
+    /// The two base hashes of `item`, from the filter's own hashers.
+    pub(super) fn base_hashes(&self, item: &T) -> (u64, u64)
+    where
+        T: Hash,
+    {
+        self.hashers.hash_item(item)
+    }
+
+    /// Inserts every pair of base hashes in `hashes`, setting their bits one block of the bit
+    /// array at a time rather than item by item. See [`BulkBuilder`](super::BulkBuilder).
+    pub(super) fn insert_hashes_blocked(&mut self, hashes: &[(u64, u64)]) {
+        // 32 KiB of bits per block, and a batch of about a million positions
+        const BLOCK_BITS: usize = 1 << 18;
+        const BATCH: usize = 1 << 17;
+        let blocks = self.m.div_ceil(BLOCK_BITS);
+        let mut starts = vec![0; blocks + 1];
+        let mut positions = Vec::new();
+        let mut blocked = Vec::new();
+        for batch in hashes.chunks(BATCH) {
+            positions.clear();
+            positions.extend(
+                batch
+                    .iter()
+                    .flat_map(|&(h1, h2)| double_hash_positions(h1, h2, self.k, self.m)),
+            );
+            // Counting sort by block
+            starts.fill(0);
+            for &pos in &positions {
+                starts[pos / BLOCK_BITS + 1] += 1;
+            }
+            for block in 0..blocks {
+                starts[block + 1] += starts[block];
+            }
+            blocked.resize(positions.len(), 0);
+            for &pos in &positions {
+                let next = &mut starts[pos / BLOCK_BITS];
+                blocked[*next] = pos;
+                *next += 1;
+            }
+            for &pos in &blocked {
+                self.bit_array.set(pos, true);
+            }
+        }
+        for &(h1, h2) in hashes {
+            self.inserted.record(h1, h2);
+        }
+        self.count += hashes.len();
+    }
+
+    /// Checks membership given two precomputed base hashes. See [`insert_hash`](Self::insert_hash).
+    pub fn contains_hash(&self, h1: u64, h2: u64) -> bool {
+        let found =
+            double_hash_positions(h1, h2, self.k, self.m).all(|pos| self.bit_array.get(pos));
+        self.inserted.check(h1, h2, found);
+        found
+    }
+
+    /// Inserts an item from hashes computed by [`hash_item`](crate::hashing::hash_item) with
+    /// this filter's hasher type and [`base_seeds`](Self::base_seeds).
+    ///
+    /// The bits set are exactly those [`insert`](ApproximateMembershipQuery::insert) would set.
+    /// Filters built [`with_hasher_instance`](Self::with_hasher_instance) don't hash from seeds
+    /// and must be fed through [`insert_hash`](Self::insert_hash) instead.
+    pub fn insert_precomputed(&mut self, hashes: &PrecomputedHashes) {
+        self.insert_hash(hashes.first, hashes.second);
+    }
+
+    /// Checks membership from precomputed hashes. See
+    /// [`insert_precomputed`](Self::insert_precomputed).
+    pub fn contains_precomputed(&self, hashes: &PrecomputedHashes) -> bool {
+        self.contains_hash(hashes.first, hashes.second)
+    }
+
+    /// Inserts a raw byte-slice key, hashing the bytes directly.
+    ///
+    /// This skips the `Hash` pass that [`insert`](ApproximateMembershipQuery::insert) makes, so
+    /// it is the fast path for keys that already are bytes (lines, serialized ids). Byte keys
+    /// map to different bits than the same data inserted as a `T`, so look them up with
+    /// [`contains_bytes`](Self::contains_bytes).
+    pub fn insert_bytes(&mut self, key: &[u8]) {
+        let (h1, h2) = self.hashers.hash_bytes(key);
+        self.insert_hash(h1, h2);
+    }
+
+    /// Checks membership of a key inserted with [`insert_bytes`](Self::insert_bytes).
+    pub fn contains_bytes(&self, key: &[u8]) -> bool {
+        let (h1, h2) = self.hashers.hash_bytes(key);
+        self.contains_hash(h1, h2)
+    }
+
+    /// Checks membership hashing `item` with `H2` instead of the filter's hasher, seeded with
+    /// the filter's [`base_seeds`](Self::base_seeds).
+    ///
+    /// **For analysis only.** Unless `H2` hashes exactly like the hasher the items were inserted
+    /// with, it probes unrelated bits: inserted items are reported absent, breaking the
+    /// no-false-negatives guarantee, and the answer for anything is just a false positive at
+    /// the current fill. This shows why build-time and query-time hashers must match.
+    pub fn contains_with<H2: Hasher64>(&self, item: &T) -> bool
+    where
+        T: Hash,
+    {
+        let hashes = hash_item::<H2, T>(item, self.base_seeds());
+        double_hash_positions(hashes.first, hashes.second, self.k, self.m)
+            .all(|pos| self.bit_array.get(pos))
+    }
+
+    /// Inserts every line of `reader` through [`insert_bytes`](Self::insert_bytes) and returns
+    /// the number of keys inserted.
+    ///
+    /// Line endings (`\n` or `\r\n`) are stripped and empty lines are skipped, so
+    /// `cat keys.txt | tool` loads exactly one key per non-empty line.
+    pub fn ingest_lines<R: BufRead>(&mut self, mut reader: R) -> io::Result<usize> {
+        let mut line = Vec::new();
+        let mut ingested = 0;
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(ingested);
+            }
+            let key = line
+                .strip_suffix(b"\n")
+                .map_or(&line[..], |key| key.strip_suffix(b"\r").unwrap_or(key));
+            if !key.is_empty() {
+                self.insert_bytes(key);
+                ingested += 1;
+            }
+        }
+    }
+
+    /// Adds every item of `other` to `self` by ORing their bits, e.g. to combine filters built
+    /// from different shards of a stream without re-inserting anything.
+    ///
+    /// Afterwards `self` reports every item inserted into either filter, exactly as if it had
+    /// been fed `other`'s items too. Its [`len`](ApproximateMembershipQuery::len) becomes the sum
+    /// of both counts, an upper bound on the distinct items since items inserted into both
+    /// shards can't be told apart. The filters must be compatible as for
+    /// [`intersect_into`](Self::intersect_into); otherwise `self` is left unchanged.
+    pub fn union(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_compatible(other)?;
+        for pos in (0..self.m).filter(|&pos| other.bit_array.get(pos)) {
+            self.bit_array.set(pos, true);
+        }
+        self.count += other.count;
+        Ok(())
+    }
+
+    /// In-place [`intersect_into`](Self::intersect_into): keeps only the bits set in both
+    /// filters, and the smaller of the two counts.
+    ///
+    /// Items inserted into `self` but not `other` are then usually, but not always, reported
+    /// absent.
+    pub fn intersect(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_compatible(other)?;
+        for pos in (0..self.m).filter(|&pos| !other.bit_array.get(pos)) {
+            self.bit_array.set(pos, false);
+        }
+        self.inserted.clear();
+        self.count = self.count.min(other.count);
+        Ok(())
+    }
+
+    /// Returns a filter whose bits are the bitwise AND of `self` and `other`.
+    ///
+    /// Every item inserted into both filters is reported present by the result, so it can be
+    /// used to pre-screen candidates for the intersection. It is an over-approximation: an item
+    /// inserted into neither (or only one) filter is also reported present if each of its bits
+    /// was set in both, possibly by different items. Its false positive rate is therefore at
+    /// least that of a filter built from the true intersection.
+    ///
+    /// The result takes its capacity and target rate from `self`, and its
+    /// [`len`](ApproximateMembershipQuery::len) is the smaller of the two counts, an upper
+    /// bound on the number of common insertions.
+    ///
+    /// Both filters must have the same number of bits, hash functions, configured false
+    /// positive rate and base seeds. Filters built with
+    /// [`with_hasher_instance`](Self::with_hasher_instance) must share the same instance; this
+    /// can't be checked.
+    pub fn intersect_into(&self, other: &Self) -> Result<Self, MergeError>
+    where
+        H: Clone,
+    {
+        self.check_compatible(other)?;
+        Ok(self.combine_bits(other, self.count.min(other.count), |a, b| a && b))
+    }
+
+    /// Returns a filter whose bits are those set in `self` but not in `other`
+    /// (`self & !other`), for pre-filtering candidates for "in `self` but not in `other`".
+    ///
+    /// This is a heuristic, not a set difference. An item inserted only into `self` is dropped
+    /// whenever one of its bits was also set in `other` by some other item: it survives with
+    /// probability about `(1 - other.fill_ratio())^k`, so the result has many false negatives
+    /// unless `other` is sparse. Like any Bloom filter it also has false positives. Use it to
+    /// discard candidates cheaply before an exact check, never as the final answer.
+    ///
+    /// The result keeps `self`'s [`len`](ApproximateMembershipQuery::len), an upper bound on
+    /// the number of items it represents. Requires the same compatibility as
+    /// [`intersect_into`](Self::intersect_into).
+    pub fn difference(&self, other: &Self) -> Result<Self, MergeError>
+    where
+        H: Clone,
+    {
+        self.check_compatible(other)?;
+        Ok(self.combine_bits(other, self.count, |a, b| a && !b))
+    }
+
+    /// Returns the union of `filters`: a filter whose bits are the bitwise OR of theirs,
+    /// computed in one pass.
+    ///
+    /// The result reports every item inserted into any of the filters, exactly as a single
+    /// filter fed all of their items would. Its [`len`](ApproximateMembershipQuery::len) is the
+    /// total number of insertions. Every filter must be compatible with the first, as for
+    /// [`intersect_into`](Self::intersect_into), and an empty slice is a
+    /// [`NoFilters`](MergeError::NoFilters) error.
+    pub fn union_all(filters: &[Self]) -> Result<Self, MergeError>
+    where
+        H: Clone,
+    {
+        let first = Self::check_all_compatible(filters)?;
+        let mut bit_array = S::with_len(first.m);
+        for pos in (0..first.m).filter(|&pos| filters.iter().any(|f| f.bit_array.get(pos))) {
+            bit_array.set(pos, true);
+        }
+        Ok(BloomFilter {
+            bit_array,
+            m: first.m,
+            k: first.k,
+            n: first.n,
+            f: first.f,
+            count: filters.iter().map(|filter| filter.count).sum(),
+            seed: first.seed,
+            seed_offsets: first.seed_offsets,
+            hashers: first.hashers.clone(),
+            inserted: InsertLog::default(),
+            _phantom_data: PhantomData,
+        })
+    }
+
+    /// Estimates the number of distinct items in the union of `filters` without building it,
+    /// with the formula of [`estimated_distinct`](Self::estimated_distinct) applied to the
+    /// number of bits set in any of them. Fails like [`union_all`](Self::union_all).
+    pub fn union_cardinality_estimate(filters: &[Self]) -> Result<f64, MergeError> {
+        let first = Self::check_all_compatible(filters)?;
+        let set_bits = (0..first.m)
+            .filter(|&pos| filters.iter().any(|f| f.bit_array.get(pos)))
+            .count();
+        Ok(distinct_from_set_bits(first.m, first.k, set_bits))
+    }
+
+    /// Returns the first of `filters` after checking every other one is compatible with it.
+    fn check_all_compatible(filters: &[Self]) -> Result<&Self, MergeError> {
+        let (first, rest) = filters.split_first().ok_or(MergeError::NoFilters)?;
+        for filter in rest {
+            first.check_compatible(filter)?;
+        }
+        Ok(first)
+    }
+
+    /// A filter like `self` whose bit `i` is `op(self[i], other[i])`.
+    fn combine_bits(&self, other: &Self, count: usize, op: impl Fn(bool, bool) -> bool) -> Self
+    where
+        H: Clone,
+    {
+        let mut bit_array = S::with_len(self.m);
+        for pos in 0..self.m {
+            if op(self.bit_array.get(pos), other.bit_array.get(pos)) {
+                bit_array.set(pos, true);
+            }
+        }
+        BloomFilter {
+            bit_array,
+            m: self.m,
+            k: self.k,
+            n: self.n,
+            f: self.f,
+            count,
+            seed: self.seed,
+            seed_offsets: self.seed_offsets,
+            hashers: self.hashers.clone(),
+            inserted: InsertLog::default(),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Tests whether the set `self` was built from is (probably) a subset of `other`'s, by
+    /// checking that every bit set in `self` is also set in `other`.
+    ///
+    /// The test is one-sided: `false` always means some item of `self` was never inserted into
+    /// `other`, while `true` can be wrong when the bits of the extra items happen to be set in
+    /// `other` by collisions, which grows likelier as `other` fills up. Requires the same
+    /// compatibility as [`intersect_into`](Self::intersect_into).
+    pub fn is_subset_of(&self, other: &Self) -> Result<bool, MergeError> {
+        self.check_compatible(other)?;
+        Ok((0..self.m).all(|pos| !self.bit_array.get(pos) || other.bit_array.get(pos)))
+    }
+
+    fn check_compatible(&self, other: &Self) -> Result<(), MergeError> {
+        if self.m != other.m || self.k != other.k {
+            return Err(MergeError::ShapeMismatch);
+        }
+        if self.f != other.f {
+            return Err(MergeError::RateMismatch);
+        }
+        if self.hasher_id() != other.hasher_id() {
+            return Err(MergeError::HasherMismatch);
+        }
+        if self.base_seeds() != other.base_seeds() {
+            return Err(MergeError::SeedMismatch);
+        }
+        Ok(())
+    }
+
+    /// Serializes the filter into a flat byte buffer.
+    ///
+    /// The layout is a [`HEADER_LEN`]-byte header followed by the bit array packed into
+    /// `m.div_ceil(8)` bytes (most significant bit first).
+    ///
+    /// # Portability
+    ///
+    /// Only keys inserted with [`insert_bytes`](Self::insert_bytes) (or through a
+    /// [`ByteBloomFilter`](crate::filters::bloom::ByteBloomFilter)) are found again by any
+    /// build. Items of type `T` are first reduced to a digest with their `Hash` impl and the
+    /// standard library's `DefaultHasher`, whose output is unspecified across Rust releases, so
+    /// a `T`-keyed filter reloaded by a binary built with another toolchain can miss every
+    /// item. Persist those only for the same build to read back.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.push(self.hashers.from_instance as u8);
+        bytes.extend_from_slice(&hasher_fingerprint(self.hasher_id()).to_le_bytes());
+        bytes.extend_from_slice(&(self.m as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.k as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.n as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.f.to_le_bytes());
+        bytes.extend_from_slice(&(self.count as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        for offset in self.seed_offsets {
+            bytes.extend_from_slice(&offset.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.bit_array.as_bytes());
+        bytes
+    }
+
+    /// Returns the number of bytes [`as_bytes`](Self::as_bytes) would produce, without
+    /// serializing.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_LEN + self.m.div_ceil(8)
+    }
+
+    /// Decodes a filter serialized by [`as_bytes`](Self::as_bytes).
+    ///
+    /// The input is fully validated before anything is allocated: the header's parameters must
+    /// be usable and the bit array must be exactly `m.div_ceil(8)` bytes, so a corrupt or
+    /// hostile header can't request more memory than the input itself occupies.
+    ///
+    /// The hashers are rebuilt from the stored seeds with `H::with_seed`, and must be the
+    /// algorithm the filter was built with: a different `H`, or a boxed
+    /// [`DynHasher64`](crate::hashing::DynHasher64) (whose `with_seed` is always an
+    /// [`XXHasher`](crate::hashing::XXHasher)) for a filter that hashed otherwise, fails with
+    /// [`DecodeError::HasherMismatch`]. Filters built
+    /// [`with_hasher_instance`](Self::with_hasher_instance) can't be decoded at all.
+    ///
+    /// Nothing in the header can tell whether `T`-keyed items still hash as they did when the
+    /// filter was written; see [`as_bytes`](Self::as_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let header = Header::decode(bytes)?;
+        header.validate()?;
+        let hashers = header.hashers()?;
+        let bits = &bytes[HEADER_LEN..];
+        if bits.len() != header.m.div_ceil(8) {
+            return Err(DecodeError::LengthMismatch);
+        }
+
+        let mut bit_array = S::with_len(header.m);
+        for pos in (0..header.m).filter(|pos| bits[pos / 8] & (0x80 >> (pos % 8)) != 0) {
+            bit_array.set(pos, true);
+        }
+        Ok(BloomFilter {
+            bit_array,
+            m: header.m,
+            k: header.k,
+            n: header.n,
+            f: header.f,
+            count: header.count,
+            seed: header.seed,
+            seed_offsets: header.seed_offsets,
+            hashers,
+            inserted: InsertLog::default(),
+            _phantom_data: PhantomData,
+        })
+    }
+
+    /// Number of bits in the filter (`m`).
+    pub fn num_bits(&self) -> usize {
+        self.m
+    }
+
+    /// Number of bits of memory backing the filter: [`num_bits`](Self::num_bits) rounded up to
+    /// the storage's word size. Positions are only ever drawn from the first `num_bits`.
+    pub fn allocated_bits(&self) -> usize {
+        self.bit_array.allocated_bits()
+    }
+
+    /// Bytes of memory backing the bit array, i.e. [`allocated_bits`](Self::allocated_bits)
+    /// in bytes. The filter's other fields add a constant few dozen bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.allocated_bits().div_ceil(8)
+    }
+
+    /// How many times less memory the filter takes than an exact `HashSet<T>` of the inserted
+    /// items, each `avg_key_bytes` in place.
+    ///
+    /// The set's footprint is estimated from the standard library's table layout: a power of
+    /// two number of buckets kept at most 7/8 full, each holding a key and one control byte.
+    /// Heap data a key owns (a `String`'s contents, say) and allocator overhead are not
+    /// counted, so for such keys the real savings are larger. Items are assumed distinct, as
+    /// [`len`](ApproximateMembershipQuery::len) counts repeated insertions.
+    pub fn space_savings_vs_exact(&self, avg_key_bytes: usize) -> f64 {
+        let buckets = if self.count == 0 {
+            0
+        } else {
+            (self.count * 8).div_ceil(7).next_power_of_two()
+        };
+        (buckets * (avg_key_bytes + 1)) as f64 / self.memory_bytes() as f64
+    }
+
+    /// Number of hash functions (`k`).
+    pub fn num_hashes(&self) -> usize {
+        self.k
+    }
+
+    /// Number of bits currently set.
+    pub fn set_bits(&self) -> usize {
+        self.bit_array.count_ones()
+    }
+
+    /// Fraction of bits currently set, in `[0, 1]`.
+    pub fn fill_ratio(&self) -> f64 {
+        self.set_bits() as f64 / self.m as f64
+    }
+
+    /// Number of set bits in each of `buckets` contiguous, near-equal ranges of the bit array.
+    ///
+    /// With well-distributed hashes the counts are roughly equal; a strongly skewed histogram
+    /// points at clustering, e.g. from an adversary exploiting a known seed.
+    pub fn fill_histogram(&self, buckets: usize) -> Vec<usize> {
+        assert!(
+            buckets > 0 && buckets <= self.m,
+            "Buckets must be between 1 and the number of bits"
+        );
+        (0..buckets)
+            .map(|bucket| {
+                let (start, end) = (bucket * self.m / buckets, (bucket + 1) * self.m / buckets);
+                (start..end).filter(|&pos| self.bit_array.get(pos)).count()
+            })
+            .collect()
+    }
+
+    /// False positive rate implied by the current fill: `fill_ratio^k`.
+    ///
+    /// Unlike the configured rate, this grows as items are inserted and exceeds the target
+    /// once the filter is filled past its capacity.
+    pub fn current_false_positive_rate(&self) -> f64 {
+        self.fill_ratio().powi(self.k as i32)
+    }
+
+    /// Theoretical false positive rate after `items` insertions: `(1 - e^(-k * items / m))^k`.
+    ///
+    /// Depends only on the filter's dimensions, so it can be evaluated for any load, e.g. to
+    /// decide how far past capacity a filter can be pushed.
+    pub fn fpr_at_load(&self, items: usize) -> f64 {
+        let (m, k) = (self.m as f64, self.k as f64);
+        (1.0 - (-k * items as f64 / m).exp()).powf(k)
+    }
+
+    /// Number of items the filter's dimensions are optimal for: `floor((m / k) * ln 2)`.
+    ///
+    /// At this load exactly half the bits are expected to be set and the false positive rate
+    /// is `2^-k`, the configured rate up to the rounding of `k`. It can differ slightly from
+    /// the capacity the filter was created with, and tells how much headroom the sizing really
+    /// leaves.
+    pub fn optimal_capacity(&self) -> usize {
+        (self.m as f64 / self.k as f64 * std::f64::consts::LN_2).floor() as usize
+    }
+
+    /// Increase of [`fpr_at_load`](Self::fpr_at_load) caused by one more insertion at the
+    /// current load.
+    ///
+    /// This grows smoothly as the filter fills (up to roughly `ln(k) / ln(2)` times the
+    /// capacity), which makes it a softer rotation signal than a hard threshold.
+    pub fn marginal_fpr_increase(&self) -> f64 {
+        self.fpr_at_load(self.count + 1) - self.fpr_at_load(self.count)
+    }
+
+    /// Cost of the filter's `k` relative to the best integer `k` for its size: the ratio of
+    /// their false positive rates at capacity, always `>= 1`.
+    ///
+    /// `1.0` means `k` is the better of the two integers around the fractional optimum
+    /// `(m / n) * ln 2`, which [`KRounding::Nearest`] almost always picks. A filter built with
+    /// [`KRounding::Ceil`] or [`with_fixed_k`](Self::with_fixed_k) can be above it, e.g. `1.02`
+    /// means 2% more false positives than the same bits could give.
+    pub fn k_efficiency(&self) -> f64 {
+        let optimal = (self.m as f64 / self.n as f64) * std::f64::consts::LN_2;
+        let fpr_with_k = |k: f64| (1.0 - (-k * self.n as f64 / self.m as f64).exp()).powf(k);
+        let best = fpr_with_k(optimal.floor().max(1.0)).min(fpr_with_k(optimal.ceil().max(1.0)));
+        self.fpr_at_load(self.n) / best
+    }
+
+    /// Estimates the number of distinct items inserted from the fill (Swamidass & Baldi):
+    /// `-(m / k) * ln(1 - X / m)` where `X` is the number of set bits.
+    ///
+    /// Unlike [`len`](ApproximateMembershipQuery::len), duplicate insertions aren't counted.
+    /// Returns infinity once every bit is set.
+    pub fn estimated_distinct(&self) -> f64 {
+        distinct_from_set_bits(self.m, self.k, self.set_bits())
+    }
+
+    pub(crate) fn calculate_m(n: usize, f: f64) -> Result<usize, SizingError> {
+        if !(f > 0.0 && f < 1.0) {
+            return Err(SizingError::InvalidParameters);
+        }
+        checked_bits(-(n as f64) * f.ln() / (2f64.ln().powi(2)))
+    }
+
+    fn calculate_m_for_k(n: usize, f: f64, k: usize) -> Result<usize, SizingError> {
+        if !(f > 0.0 && f < 1.0) {
+            return Err(SizingError::InvalidParameters);
+        }
+        let k = k as f64;
+        checked_bits(-k * n as f64 / (1.0 - f.powf(1.0 / k)).ln())
+    }
+
+    pub(crate) fn calculate_k(m: usize, n: usize, rounding: KRounding) -> usize {
+        let optimal = (m as f64 / n as f64) * 2f64.ln();
+        let k = match rounding {
+            KRounding::Nearest => optimal.round(),
+            KRounding::Ceil => optimal.ceil(),
+        };
+        (k as usize).max(1)
+    }
+}
+
+/// Filter parameters decoded from a serialization header.
+pub(super) struct Header {
+    from_instance: bool,
+    hasher_fingerprint: u64,
+    pub(super) m: usize,
+    pub(super) k: usize,
+    pub(super) n: usize,
+    pub(super) f: f64,
+    pub(super) count: usize,
+    pub(super) seed: u64,
+    pub(super) seed_offsets: [u64; 2],
+}
+
+impl Header {
+    /// Decodes the header at the start of `bytes`, failing if it is truncated, the magic bytes
+    /// don't match or it was written by another version of the format.
+    pub(super) fn decode(bytes: &[u8]) -> Result<Header, DecodeError> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(DecodeError::BadHeader);
+        }
+        let (version, from_instance) = (bytes[MAGIC.len()], bytes[MAGIC.len() + 1]);
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion);
+        }
+        if from_instance > 1 {
+            return Err(DecodeError::BadHeader);
+        }
+        // The fingerprint, then the parameters
+        let field = |i: usize| {
+            let start = MAGIC.len() + 2 + i * 8;
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[start..start + 8]);
+            buf
+        };
+        Ok(Header {
+            from_instance: from_instance == 1,
+            hasher_fingerprint: u64::from_le_bytes(field(0)),
+            m: u64::from_le_bytes(field(1)) as usize,
+            k: u64::from_le_bytes(field(2)) as usize,
+            n: u64::from_le_bytes(field(3)) as usize,
+            f: f64::from_le_bytes(field(4)),
+            count: u64::from_le_bytes(field(5)) as usize,
+            seed: u64::from_le_bytes(field(6)),
+            seed_offsets: [u64::from_le_bytes(field(7)), u64::from_le_bytes(field(8))],
+        })
+    }
+
+    /// Rebuilds the filter's hashers from its seeds, failing unless `H` hashes like the ones it
+    /// was serialized with.
+    pub(super) fn hashers<H: Hasher64>(&self) -> Result<BaseHashers<H>, DecodeError> {
+        if self.from_instance {
+            return Err(DecodeError::InstanceHasher);
+        }
+        let hashers = BaseHashers::<H>::from_seeds(self.base_seeds());
+        if hasher_fingerprint(hashers.first.hasher_id()) != self.hasher_fingerprint {
+            return Err(DecodeError::HasherMismatch);
+        }
+        Ok(hashers)
+    }
+
+    pub(super) fn base_seeds(&self) -> [u64; 2] {
+        base_seeds(self.seed, self.seed_offsets)
+    }
+
+    /// Checks that the parameters describe a usable filter.
+    pub(super) fn validate(&self) -> Result<(), DecodeError> {
+        let valid = self.m > 0
+            && (1..=MAX_DECODED_HASHES).contains(&self.k)
+            && self.n > 0
+            && self.f > 0.0
+            && self.f < 1.0
+            && self.seed_offsets[0] != self.seed_offsets[1];
+        if valid {
+            Ok(())
+        } else {
+            Err(DecodeError::InvalidParameters)
+        }
+    }
+}
+
+/// The two hash functions the base hashes are computed with.
+#[derive(Clone)]
+pub(crate) struct BaseHashers<H> {
+    first: H,
+    second: H,
+    /// Whether these come from [`from_instance`](Self::from_instance), and so can't be rebuilt
+    /// from the seeds.
+    from_instance: bool,
+}
+
+impl<H: Hasher64> BaseHashers<H> {
+    /// Hashers seeded with each of `seeds`.
+    pub(crate) fn from_seeds(seeds: [u64; 2]) -> Self {
+        BaseHashers {
+            first: H::with_seed(seeds[0]),
+            second: H::with_seed(seeds[1]),
+            from_instance: false,
+        }
+    }
+
+    /// Hashers of the same kind as `self` seeded with each of `seeds`. Identical to
+    /// [`from_seeds`](Self::from_seeds) unless `H` is type-erased.
+    fn reseeded(&self, seeds: [u64; 2]) -> Self {
+        BaseHashers {
+            first: self.first.reseeded(seeds[0]),
+            second: self.first.reseeded(seeds[1]),
+            from_instance: false,
+        }
+    }
+
+    /// Uses `hasher` for the first base hash and a companion seeded from its output for the
+    /// second, so both depend on the instance's configuration.
+    fn from_instance(hasher: H) -> Self {
+        let second = hasher.reseeded(hasher.hash(b"bloom-second-base-hash"));
+        BaseHashers {
+            first: hasher,
+            second,
+            from_instance: true,
+        }
+    }
+
+    /// Computes the two base hash values for an item (this is where the actual hashing happens).
+    ///
+    /// Only these two hashes are computed per item; the k positions are derived from them by
+    /// double hashing, `h_i(x) = (h1(x) + i * h2(x)) mod m`, whose arithmetic is orders of
+    /// magnitude cheaper than k separate hash computations.
+    pub(crate) fn hash_item<T: Hash>(&self, item: &T) -> (u64, u64) {
+        self.hash_bytes(&item_bytes(item))
+    }
+
+    pub(super) fn hash_bytes(&self, bytes: &[u8]) -> (u64, u64) {
+        (self.first.hash(bytes), self.second.hash(bytes))
+    }
+}
+
+/// Rounds a computed number of bits up, rejecting values a `usize` cast would silently
+/// saturate or turn into an empty filter.
+fn checked_bits(bits: f64) -> Result<usize, SizingError> {
+    let bits = bits.ceil();
+    if bits.is_nan() || bits > MAX_BITS as f64 {
+        return Err(SizingError::TooManyBits);
+    }
+    if bits <= 0.0 {
+        return Err(SizingError::InvalidParameters);
+    }
+    Ok(bits as usize)
+}
+
+/// Swamidass & Baldi estimate of the distinct items that set `set_bits` of `m` bits with `k`
+/// hash functions.
+fn distinct_from_set_bits(m: usize, k: usize, set_bits: usize) -> f64 {
+    -(m as f64 / k as f64) * (1.0 - set_bits as f64 / m as f64).ln()
+}
+
+/// A stable 64-bit fingerprint (FNV-1a) of a [`hasher_id`](Hasher64::hasher_id), recorded in
+/// the serialization header.
+fn hasher_fingerprint(hasher_id: &str) -> u64 {
+    hasher_id.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// Seeds of the two base hashers for a filter seed and its offsets.
+fn base_seeds(seed: u64, seed_offsets: [u64; 2]) -> [u64; 2] {
+    seed_offsets.map(|offset| seed.wrapping_add(offset))
+}
+
+/// Capacity of a [`BloomFilter::default`] filter.
+const DEFAULT_CAPACITY: usize = 1024;
+/// False positive rate of a [`BloomFilter::default`] filter.
+const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// A small filter for 1024 items at a 1% false positive rate (about 1.2 KiB of bits, k = 7).
+///
+/// Equivalent to `BloomFilter::new(1024, 0.01)`: the hashers are seeded, not built with
+/// `H::default()`, so default filters are reproducible and compatible with each other even
+/// for hashers like [`AHasher`](crate::hashing::AHasher) whose `Default` is randomly keyed.
+impl<T, H: Hasher64, S: BitStorage> Default for BloomFilter<T, H, S> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_FALSE_POSITIVE_RATE)
+    }
+}
+
+impl<T: Hash, H: Hasher64, S: BitStorage> ApproximateMembershipQuery<T> for BloomFilter<T, H, S> {
+    fn insert(&mut self, item: &T) {
+        let (h1, h2) = self.hashers.hash_item(item);
+        self.insert_hash(h1, h2);
+    }
+
     fn contains(&self, item: &T) -> bool {
-        self.hash_positions(item).all(|pos| self.bit_array[pos])
+        let (h1, h2) = self.hashers.hash_item(item);
+        self.contains_hash(h1, h2)
     }
 
     fn false_positive_rate(&self) -> f64 {
         self.f
     }
 
+    /// `(1 - e^(-k * n / m))^k` at the capacity `n`: [`fpr_at_load`](BloomFilter::fpr_at_load)
+    /// for the designed load.
+    fn theoretical_false_positive_rate(&self) -> f64 {
+        self.fpr_at_load(self.n)
+    }
+
     fn capacity(&self) -> usize {
         self.n
     }
@@ -108,17 +1117,55 @@ impl<T: Hash, H: Hasher64> ApproximateMembershipQuery<T> for BloomFilter<T, H> {
     fn len(&self) -> usize {
         self.count
     }
+
+    fn clear(&mut self) {
+        self.bit_array.clear();
+        self.inserted.clear();
+        self.count = 0;
+    }
+}
+
+/// Jaccard similarity `|A ∩ B| / |A ∪ B|` of the inserted sets, from the distinct counts
+/// [estimated](BloomFilter::estimated_distinct) for each filter and for their union, with the
+/// intersection by inclusion-exclusion.
+///
+/// The estimate is clamped to `[0, 1]` and two empty filters are identical. It is noisy when
+/// the intersection is small relative to the union, and meaningless (NaN) once the union has
+/// every bit set.
+impl<T, H: Hasher64, S: BitStorage> SimilarityEstimator for BloomFilter<T, H, S> {
+    fn similarity(&self, other: &Self) -> Result<f64, MergeError> {
+        self.check_compatible(other)?;
+        let union_bits = (0..self.m)
+            .filter(|&pos| self.bit_array.get(pos) || other.bit_array.get(pos))
+            .count();
+        if union_bits == 0 {
+            return Ok(1.0);
+        }
+        let union = distinct_from_set_bits(self.m, self.k, union_bits);
+        let intersection = self.estimated_distinct() + other.estimated_distinct() - union;
+        Ok((intersection / union).clamp(0.0, 1.0))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::hashing::AHasher;
+    use crate::filters::bloom::DenseBits;
+    use crate::hashing::{AHasher, DynHasher64, XXHasher};
+    use rand::Rng;
+
+    fn positions<S: BitStorage>(
+        bf: &BloomFilter<u64, AHasher, S>,
+        item: &u64,
+    ) -> impl Iterator<Item = usize> + use<S> {
+        let (h1, h2) = bf.hashers.hash_item(item);
+        double_hash_positions(h1, h2, bf.num_hashes(), bf.num_bits())
+    }
 
     #[test]
     fn test_calculate_m() {
         // For n=1000, f=0.01, m should be ~9585
-        let m = BloomFilter::<u64, AHasher>::calculate_m(1000, 0.01);
+        let m = BloomFilter::<u64, AHasher>::calculate_m(1000, 0.01).unwrap();
         assert!((9500..=9600).contains(&m));
     }
 
@@ -126,8 +1173,193 @@ mod tests {
     fn test_calculate_k() {
         let m = 9585;
         let n = 1000;
-        let k = BloomFilter::<u64, AHasher>::calculate_k(m, n);
-        assert_eq!(k, 7); // k ≈ 6.64 → 7
+        let k = |m, rounding| BloomFilter::<u64, AHasher>::calculate_k(m, n, rounding);
+        assert_eq!(k(m, KRounding::Nearest), 7); // k ≈ 6.64 → 7
+        assert_eq!(k(m, KRounding::Ceil), 7);
+        assert_eq!(k(9233, KRounding::Nearest), 6); // k ≈ 6.40 → 6
+        assert_eq!(k(9233, KRounding::Ceil), 7);
+        assert_eq!(k(100, KRounding::Nearest), 1); // k ≈ 0.07, never 0
+    }
+
+    #[test]
+    fn test_nearest_rounding_lands_closer_to_target() {
+        // Optimal k ≈ 1.4: ceil overshoots to 2
+        let fpr = 0.379;
+        let nearest = BloomFilter::<u64, AHasher>::with_k_rounding(10_000, fpr, KRounding::Nearest);
+        let ceil = BloomFilter::<u64, AHasher>::with_k_rounding(10_000, fpr, KRounding::Ceil);
+        assert_eq!((nearest.num_hashes(), ceil.num_hashes()), (1, 2));
+
+        let theoretical_error =
+            |bf: &BloomFilter<u64, AHasher>| (bf.fpr_at_load(10_000) - fpr).abs();
+        assert!(theoretical_error(&nearest) < theoretical_error(&ceil));
+
+        let empirical = |mut bf: BloomFilter<u64, AHasher>| {
+            for i in 0..10_000u64 {
+                bf.insert(&i);
+            }
+            (10_000..210_000u64).filter(|i| bf.contains(i)).count() as f64 / 200_000.0
+        };
+        assert!((empirical(nearest) - fpr).abs() < (empirical(ceil) - fpr).abs());
+    }
+
+    #[test]
+    fn test_similarity() {
+        let filled = |keys: std::ops::Range<u64>| {
+            let mut bf = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+            keys.for_each(|key| bf.insert(&key));
+            bf
+        };
+        let a = filled(0..5000);
+
+        assert!((a.similarity(&filled(0..5000)).unwrap() - 1.0).abs() < 1e-9);
+        assert!(a.similarity(&filled(5000..10_000)).unwrap() < 0.02);
+        // 2500 shared out of 7500
+        let partial = a.similarity(&filled(2500..7500)).unwrap();
+        assert!((partial - 1.0 / 3.0).abs() < 0.02, "similarity {partial}");
+
+        let empty = filled(0..0);
+        assert_eq!(empty.similarity(&filled(0..0)).unwrap(), 1.0);
+        assert_eq!(a.similarity(&empty).unwrap(), 0.0);
+
+        let other_shape = BloomFilter::<u64, AHasher>::new(100, 0.01);
+        assert_eq!(a.similarity(&other_shape), Err(MergeError::ShapeMismatch));
+    }
+
+    #[test]
+    fn test_contains_with_mismatched_hasher() {
+        let mut bf = BloomFilter::<u64, XXHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            bf.insert(&i);
+        }
+        assert!((0..10_000u64).all(|i| bf.contains_with::<XXHasher>(&i)));
+
+        let found = (0..10_000u64)
+            .filter(|i| bf.contains_with::<AHasher>(i))
+            .count();
+        assert!(found < 200, "{} items found with the wrong hasher", found);
+    }
+
+    #[test]
+    fn test_space_savings_vs_exact() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1_000_000, 0.01);
+        assert_eq!(bf.memory_bytes(), bf.allocated_bits() / 8);
+        assert_eq!(bf.space_savings_vs_exact(8), 0.0);
+        for i in 0..1_000_000u64 {
+            bf.insert(&i);
+        }
+
+        // 2^21 buckets of 9 bytes against 9.6M bits: about 16x
+        let savings = bf.space_savings_vs_exact(8);
+        assert!((10.0..30.0).contains(&savings), "savings {savings}");
+        assert!(bf.space_savings_vs_exact(32) > savings * 3.0);
+    }
+
+    #[test]
+    fn test_union_and_intersect_in_place() {
+        let filled = |keys: std::ops::Range<u64>| {
+            let mut bf = BloomFilter::<u64, AHasher>::new(20_000, 0.01);
+            keys.for_each(|key| bf.insert(&key));
+            bf
+        };
+
+        // Disjoint shards
+        let mut merged = filled(0..5000);
+        merged.union(&filled(5000..10_000)).unwrap();
+        assert!((0..10_000u64).all(|i| merged.contains(&i)));
+        assert_eq!(merged.len(), 10_000);
+
+        // Overlapping shards: the count double-counts the overlap
+        let mut merged = filled(0..6000);
+        let other = filled(4000..10_000);
+        merged.union(&other).unwrap();
+        assert!((0..10_000u64).all(|i| merged.contains(&i)));
+        assert_eq!(merged.len(), 12_000);
+        assert_eq!(merged.bit_array, filled(0..10_000).bit_array);
+
+        let mut common = filled(0..6000);
+        common.intersect(&other).unwrap();
+        assert!((4000..6000u64).all(|i| common.contains(&i)));
+        let kept = (0..4000u64).filter(|i| common.contains(i)).count();
+        assert!(kept < 400, "{} items of self only kept", kept);
+        assert_eq!(common.len(), 6000);
+
+        let mut small = BloomFilter::<u64, AHasher>::new(100, 0.01);
+        let before = small.bit_array.clone();
+        assert_eq!(small.union(&other), Err(MergeError::ShapeMismatch));
+        assert_eq!(small.intersect(&other), Err(MergeError::ShapeMismatch));
+        assert_eq!(small.bit_array, before);
+    }
+
+    #[test]
+    fn test_merging_rejects_different_rates() {
+        let mut a = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        a.insert(&1);
+        // Same bits, hashes and seeds, but configured for another rate
+        let mut b = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        b.f = 0.02;
+        assert_eq!(a.union(&b), Err(MergeError::RateMismatch));
+        assert_eq!(a.intersect(&b), Err(MergeError::RateMismatch));
+        assert_eq!(a.intersect_into(&b).err(), Some(MergeError::RateMismatch));
+        assert_eq!(a.false_positive_rate(), 0.01);
+        assert!(a.contains(&1));
+    }
+
+    #[test]
+    fn test_shared_filter_concurrent_queries() {
+        fn assert_sync<F: Sync + Send>() {}
+        assert_sync::<BloomFilter<u64, AHasher>>();
+        assert_sync::<BloomFilter<String, XXHasher, DenseBits>>();
+        assert_sync::<BloomFilter<u64, Box<dyn DynHasher64>>>();
+
+        let mut bf = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            bf.insert(&i);
+        }
+        let shared = bf.into_shared();
+
+        let handles: Vec<_> = (0..4u64)
+            .map(|thread| {
+                let filter = Arc::clone(&shared);
+                std::thread::spawn(move || {
+                    let present = (thread * 2500..(thread + 1) * 2500).all(|i| filter.contains(&i));
+                    let false_positives =
+                        (10_000..20_000u64).filter(|i| filter.contains(i)).count();
+                    (present, false_positives)
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(results.iter().all(|&(present, _)| present));
+        assert!(results.windows(2).all(|pair| pair[0].1 == pair[1].1));
+        assert!(results[0].1 < 200);
+    }
+
+    #[test]
+    fn test_theoretical_false_positive_rate() {
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let theoretical = bf.theoretical_false_positive_rate();
+        assert_ne!(theoretical, bf.false_positive_rate());
+        assert!((theoretical - 0.01).abs() < 0.001, "{}", theoretical);
+
+        // Optimal k ≈ 1.4: ceil rounds up to 2 and lands well off the target
+        let ceil = BloomFilter::<u64, AHasher>::with_k_rounding(10_000, 0.379, KRounding::Ceil);
+        assert!((ceil.theoretical_false_positive_rate() - 0.379).abs() > 0.01);
+        assert_eq!(ceil.false_positive_rate(), 0.379);
+    }
+
+    #[test]
+    fn test_k_efficiency() {
+        // Optimal k ≈ 6.4: ceil rounds up to 7, nearest picks the best integer 6
+        let fpr = 2f64.powf(-6.4);
+        let nearest = BloomFilter::<u64, AHasher>::with_k_rounding(1000, fpr, KRounding::Nearest);
+        let ceil = BloomFilter::<u64, AHasher>::with_k_rounding(1000, fpr, KRounding::Ceil);
+        assert_eq!((nearest.num_hashes(), ceil.num_hashes()), (6, 7));
+
+        assert_eq!(nearest.k_efficiency(), 1.0);
+        assert!(ceil.k_efficiency() > 1.0 && ceil.k_efficiency() < 1.05);
+
+        let fixed = BloomFilter::<u64, AHasher>::with_fixed_k(1000, 0.01, 2);
+        assert!(fixed.k_efficiency() > 1.5);
     }
 
     #[test]
@@ -154,4 +1386,651 @@ mod tests {
             assert!(bf.contains(item), "False negative for {}", item);
         }
     }
+
+    #[test]
+    fn test_hash_path_agrees_with_item_path() {
+        let mut by_item = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let mut by_hash = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            let (h1, h2) = by_hash.hashers.hash_item(&i);
+            by_item.insert(&i);
+            by_hash.insert_hash(h1, h2);
+        }
+
+        assert_eq!(by_item.as_bytes(), by_hash.as_bytes());
+        for i in 0..2000u64 {
+            let (h1, h2) = by_item.hashers.hash_item(&i);
+            assert_eq!(by_item.contains(&i), by_item.contains_hash(h1, h2));
+        }
+    }
+
+    #[test]
+    fn test_shared_hasher_instance_maps_to_same_bits() {
+        // A randomly keyed instance, shared between two filters
+        let hasher = AHasher::new();
+        let mut a = BloomFilter::<u64, AHasher>::with_hasher_instance(1000, 0.01, hasher.clone());
+        let mut b = BloomFilter::<u64, AHasher>::with_hasher_instance(1000, 0.01, hasher);
+        let mut other =
+            BloomFilter::<u64, AHasher>::with_hasher_instance(1000, 0.01, AHasher::new());
+        for i in 0..1000u64 {
+            a.insert(&i);
+            b.insert(&i);
+            other.insert(&i);
+        }
+
+        assert_eq!(a.bit_array, b.bit_array);
+        assert_ne!(
+            a.bit_array, other.bit_array,
+            "Differently keyed hashers should differ"
+        );
+        for i in 0..1000u64 {
+            assert!(a.contains(&i) && b.contains(&i) && other.contains(&i));
+        }
+    }
+
+    #[test]
+    fn test_fill_accessors() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        assert_eq!(bf.set_bits(), 0);
+        assert_eq!(bf.current_false_positive_rate(), 0.0);
+        assert_eq!(bf.estimated_distinct(), 0.0);
+
+        for i in 0..1000u64 {
+            bf.insert(&i);
+            bf.insert(&i); // duplicates don't change the fill
+        }
+        assert!(bf.set_bits() <= bf.num_bits());
+        assert!((bf.fill_ratio() - 0.5).abs() < 0.05, "Optimal fill is ~50%");
+        assert!((bf.current_false_positive_rate() - 0.01).abs() < 0.005);
+        assert!((bf.estimated_distinct() - 1000.0).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_fpr_at_load() {
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        assert_eq!(bf.fpr_at_load(0), 0.0);
+        assert!((bf.fpr_at_load(1000) - 0.01).abs() < 0.002);
+        assert!(bf.fpr_at_load(2000) > bf.fpr_at_load(1000));
+    }
+
+    #[test]
+    fn test_optimal_capacity() {
+        for (capacity, fpr) in [(1000, 0.01), (50_000, 0.001), (777, 0.05), (10_000, 1e-6)] {
+            let bf = BloomFilter::<u64, AHasher>::new(capacity, fpr);
+            let optimal = bf.optimal_capacity();
+            let drift = (optimal as f64 - capacity as f64).abs() / capacity as f64;
+            assert!(drift < 0.1, "optimal {} for capacity {}", optimal, capacity);
+            let at_optimal = bf.fpr_at_load(optimal);
+            assert!(
+                (at_optimal - fpr).abs() / fpr < 0.3,
+                "FPR {} at optimal capacity, configured {}",
+                at_optimal,
+                fpr
+            );
+        }
+
+        // With an integral optimal k there is no rounding to account for
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.5f64.powi(7));
+        assert!((bf.optimal_capacity() as i64 - 1000).abs() <= 1);
+        assert!((bf.fpr_at_load(bf.optimal_capacity()) - 0.5f64.powi(7)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_with_fixed_k_rejects_out_of_range_rates() {
+        for rate in [0.0, -0.01, 1.0, 1.5, f64::NAN] {
+            let result = std::panic::catch_unwind(|| {
+                BloomFilter::<u64, AHasher>::with_fixed_k(1000, rate, 3)
+            });
+            let message = *result.err().unwrap().downcast::<String>().unwrap();
+            assert!(message.contains("rate within (0, 1)"), "rate {}", rate);
+        }
+    }
+
+    #[test]
+    fn test_with_fixed_k() {
+        for k in [1, 3, 7, 12] {
+            let bf = BloomFilter::<u64, AHasher>::with_fixed_k(10_000, 0.01, k);
+            assert_eq!(bf.num_hashes(), k);
+            let at_capacity = bf.fpr_at_load(10_000);
+            assert!(at_capacity <= 0.01, "k={} FPR {}", k, at_capacity);
+            // The smallest such m: one bit fewer already misses the target
+            let fewer = (bf.num_bits() - 1) as f64;
+            let k_f = k as f64;
+            assert!((1.0 - (-k_f * 10_000.0 / fewer).exp()).powf(k_f) > 0.01);
+        }
+
+        // At the optimal k the size matches the default sizing
+        let fixed = BloomFilter::<u64, AHasher>::with_fixed_k(10_000, 0.5f64.powi(7), 7);
+        let optimal = BloomFilter::<u64, AHasher>::new(10_000, 0.5f64.powi(7));
+        let diff = fixed.num_bits().abs_diff(optimal.num_bits());
+        assert!(
+            diff < 10,
+            "{} vs {} bits",
+            fixed.num_bits(),
+            optimal.num_bits()
+        );
+
+        let mut bf = BloomFilter::<u64, AHasher>::with_fixed_k(10_000, 0.01, 3);
+        for i in 0..10_000u64 {
+            bf.insert(&i);
+        }
+        let false_positives = (10_000..110_000u64).filter(|i| bf.contains(i)).count();
+        assert!(
+            false_positives < 1100,
+            "{} false positives",
+            false_positives
+        );
+    }
+
+    #[test]
+    fn test_marginal_fpr_increase_grows_with_fill() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let mut previous = bf.marginal_fpr_increase();
+        for i in 0..1000u64 {
+            bf.insert(&i);
+            if i % 100 == 99 {
+                let marginal = bf.marginal_fpr_increase();
+                assert!(marginal > previous, "{} <= {} at {}", marginal, previous, i);
+                previous = marginal;
+            }
+        }
+    }
+
+    #[test]
+    fn test_ingest_lines() {
+        let input = b"alpha\nbeta\r\n\n\r\ngamma\r\ndelta";
+        let mut bf = BloomFilter::<u64, AHasher>::new(100, 0.001);
+        assert_eq!(bf.ingest_lines(&input[..]).unwrap(), 4);
+        assert_eq!(bf.len(), 4);
+
+        for key in ["alpha", "beta", "gamma", "delta"] {
+            assert!(bf.contains_bytes(key.as_bytes()), "Missing {}", key);
+        }
+        for key in ["", "\r", "beta\r", "epsilon"] {
+            assert!(!bf.contains_bytes(key.as_bytes()), "Unexpected {:?}", key);
+        }
+    }
+
+    #[test]
+    fn test_intersect_into() {
+        let mut a = BloomFilter::<u64, AHasher>::new(2000, 0.01);
+        let mut b = BloomFilter::<u64, AHasher>::new(2000, 0.01);
+        for i in 0..1000u64 {
+            a.insert(&i);
+            b.insert(&(i + 500));
+        }
+
+        let both = a.intersect_into(&b).unwrap();
+        for i in 500..1000u64 {
+            assert!(both.contains(&i), "Common item {} missing", i);
+        }
+        assert!(both.set_bits() <= a.set_bits().min(b.set_bits()));
+        let only_one = (0..500u64)
+            .chain(1000..1500)
+            .filter(|i| both.contains(i))
+            .count();
+        assert!(only_one < 100, "{} single-side items reported", only_one);
+        assert_eq!(both.len(), 1000);
+    }
+
+    #[test]
+    fn test_intersect_into_rejects_incompatible_filters() {
+        let a = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let smaller = BloomFilter::<u64, AHasher>::new(500, 0.01);
+        let mut reseeded = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        reseeded.rehash_with_seed(7, std::iter::empty());
+
+        assert_eq!(
+            a.intersect_into(&smaller).err(),
+            Some(MergeError::ShapeMismatch)
+        );
+        assert_eq!(
+            a.intersect_into(&reseeded).err(),
+            Some(MergeError::SeedMismatch)
+        );
+    }
+
+    #[test]
+    fn test_difference_prefilters_candidates() {
+        // A holds 0..2000, B holds 1000..3000: only 0..1000 are in A but not B
+        let mut a = BloomFilter::<u64, AHasher>::new(4000, 0.01);
+        let mut b = BloomFilter::<u64, AHasher>::new(4000, 0.01);
+        for i in 0..2000u64 {
+            a.insert(&i);
+            b.insert(&(i + 1000));
+        }
+
+        let only_a = a.difference(&b).unwrap();
+        let kept = (0..1000u64).filter(|i| only_a.contains(i)).count();
+        let common = (1000..2000u64).filter(|i| only_a.contains(i)).count();
+        let only_b = (2000..3000u64).filter(|i| only_a.contains(i)).count();
+        assert_eq!(common, 0, "Items in both filters can never survive");
+        assert_eq!(only_b, 0);
+        // An A-only item survives only if none of its k bits is set in B
+        let expected = 1000.0 * (1.0 - b.fill_ratio()).powi(b.num_hashes() as i32);
+        assert!(
+            (kept as f64 - expected).abs() < expected * 0.5,
+            "{} of 1000 A-only items kept, expected ~{:.0}",
+            kept,
+            expected
+        );
+        assert!(only_a.set_bits() <= a.set_bits());
+        assert_eq!(only_a.len(), a.len());
+
+        assert_eq!(
+            a.difference(&BloomFilter::new(100, 0.01)).err(),
+            Some(MergeError::ShapeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_union_all() {
+        // 12 shards of 500 items, overlapping their neighbours by 100
+        let shards: Vec<BloomFilter<u64, AHasher>> = (0..12u64)
+            .map(|shard| {
+                let mut bf = BloomFilter::new(10_000, 0.01);
+                for i in shard * 400..shard * 400 + 500 {
+                    bf.insert(&i);
+                }
+                bf
+            })
+            .collect();
+
+        type Filter = BloomFilter<u64, AHasher>;
+        let copy = |bf: &Filter| Filter::from_bytes(&bf.as_bytes()).unwrap();
+        let union = BloomFilter::union_all(&shards).unwrap();
+        let pairwise = shards[1..].iter().fold(copy(&shards[0]), |acc, shard| {
+            BloomFilter::union_all(&[acc, copy(shard)]).unwrap()
+        });
+        assert_eq!(union.as_bytes(), pairwise.as_bytes());
+        assert_eq!(union.len(), 12 * 500);
+        assert!((0..11 * 400 + 500).all(|i| union.contains(&i)));
+
+        let truth = (11 * 400 + 500) as f64;
+        let estimate = BloomFilter::union_cardinality_estimate(&shards).unwrap();
+        assert!(
+            (estimate - truth).abs() / truth < 0.05,
+            "estimate {}",
+            estimate
+        );
+        assert_eq!(estimate, union.estimated_distinct());
+
+        let mut mismatched: Vec<_> = shards[..2].iter().map(copy).collect();
+        mismatched.push(BloomFilter::new(100, 0.01));
+        assert_eq!(
+            BloomFilter::union_all(&mismatched).err(),
+            Some(MergeError::ShapeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_union_of_no_filters_is_an_error() {
+        let none: &[BloomFilter<u64, AHasher>] = &[];
+        assert_eq!(
+            BloomFilter::union_all(none).err(),
+            Some(MergeError::NoFilters)
+        );
+        assert_eq!(
+            BloomFilter::union_cardinality_estimate(none).err(),
+            Some(MergeError::NoFilters)
+        );
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        let mut small = BloomFilter::<u64, AHasher>::new(2000, 0.01);
+        let mut large = BloomFilter::<u64, AHasher>::new(2000, 0.01);
+        let mut disjoint = BloomFilter::<u64, AHasher>::new(2000, 0.01);
+        for i in 0..1000u64 {
+            large.insert(&i);
+            disjoint.insert(&(i + 5000));
+            if i % 2 == 0 {
+                small.insert(&i);
+            }
+        }
+
+        assert_eq!(small.is_subset_of(&large), Ok(true));
+        assert_eq!(large.is_subset_of(&small), Ok(false));
+        assert_eq!(small.is_subset_of(&disjoint), Ok(false));
+        assert_eq!(BloomFilter::new(2000, 0.01).is_subset_of(&small), Ok(true));
+
+        small.insert(&4000);
+        assert_eq!(small.is_subset_of(&large), Ok(false));
+        assert_eq!(
+            small.is_subset_of(&BloomFilter::new(500, 0.01)),
+            Err(MergeError::ShapeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_default_filter() {
+        let mut bf = BloomFilter::<u64, AHasher>::default();
+        assert_eq!(bf.capacity(), 1024);
+        assert_eq!(bf.false_positive_rate(), 0.01);
+        for i in 0..1024u64 {
+            bf.insert(&i);
+        }
+        assert!((0..1024u64).all(|i| bf.contains(&i)));
+
+        let mut other = BloomFilter::<u64, AHasher>::default();
+        other.insert(&1);
+        assert!(bf.intersect_into(&other).unwrap().contains(&1));
+    }
+
+    #[test]
+    fn test_precomputed_path_matches_item_path() {
+        let mut by_item = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        let mut by_hashes = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        by_item.rehash_with_seed(42, std::iter::empty());
+        by_hashes.rehash_with_seed(42, std::iter::empty());
+        let seeds = by_item.base_seeds();
+        for i in 0..1000u64 {
+            by_item.insert(&i);
+            by_hashes.insert_precomputed(&hash_item::<XXHasher, _>(&i, seeds));
+        }
+
+        assert_eq!(by_item.as_bytes(), by_hashes.as_bytes());
+        for i in 0..2000u64 {
+            let hashes = hash_item::<XXHasher, _>(&i, seeds);
+            assert_eq!(by_item.contains(&i), by_item.contains_precomputed(&hashes));
+        }
+    }
+
+    #[test]
+    fn test_dyn_hashed_filters_reject_other_algorithms() {
+        let build = |hasher: Box<dyn DynHasher64>| {
+            let mut bf =
+                BloomFilter::<u64, Box<dyn DynHasher64>>::with_hasher_instance(1000, 0.01, hasher);
+            bf.insert(&7);
+            bf
+        };
+        let ahash = build(Box::new(AHasher::with_seed(0)));
+        let xxhash = build(Box::new(XXHasher::with_seed(0)));
+
+        assert_eq!(ahash.hasher_id(), "ahash");
+        assert_eq!(xxhash.hasher_id(), "xxhash3");
+        assert_eq!(
+            ahash.intersect_into(&xxhash).err(),
+            Some(MergeError::HasherMismatch)
+        );
+        let both = ahash
+            .intersect_into(&build(Box::new(AHasher::with_seed(0))))
+            .unwrap();
+        assert!(both.contains(&7));
+
+        let mut rehashed = build(Box::new(XXHasher::with_seed(0)));
+        rehashed.rehash_with_seed(5, [7u64].into_iter());
+        assert_eq!(rehashed.hasher_id(), "xxhash3");
+        assert!(rehashed.contains(&7));
+    }
+
+    #[test]
+    fn test_from_bytes_round_trip() {
+        let mut bf = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        bf.rehash_with_seed(9, 0..700u64);
+        let decoded = BloomFilter::<u64, XXHasher>::from_bytes(&bf.as_bytes()).unwrap();
+
+        assert_eq!(decoded.as_bytes(), bf.as_bytes());
+        assert_eq!(decoded.seed(), 9);
+        assert_eq!(decoded.len(), 700);
+        for i in 0..2000u64 {
+            assert_eq!(decoded.contains(&i), bf.contains(&i));
+        }
+        let dense = BloomFilter::<u64, XXHasher, DenseBits>::from_bytes(&bf.as_bytes()).unwrap();
+        assert_eq!(dense.as_bytes(), bf.as_bytes());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            bf.insert(&i);
+        }
+        bf.clear();
+
+        assert!(bf.is_empty());
+        assert_eq!(bf.set_bits(), 0);
+        assert_eq!(bf.capacity(), 1000);
+        assert!((0..1000u64).all(|i| !bf.contains(&i)));
+    }
+
+    #[test]
+    fn test_positions_stay_within_logical_bits() {
+        // 9586 bits: not a multiple of either backend's word size
+        let bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let dense = BloomFilter::<u64, AHasher, DenseBits>::new(1000, 0.01);
+        assert_eq!(bf.num_bits() % 32, 18);
+        assert!(bf.allocated_bits() >= bf.num_bits());
+        assert_eq!(dense.allocated_bits(), bf.num_bits().div_ceil(64) * 64);
+
+        for i in 0..100_000u64 {
+            assert!(positions(&bf, &i).all(|pos| pos < bf.num_bits()));
+        }
+    }
+
+    #[test]
+    fn test_contains_any_and_all() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.001);
+        for i in 0..500u64 {
+            bf.insert(&i);
+        }
+        let present: Vec<u64> = (0..10).collect();
+        let absent: Vec<u64> = (10_000..10_010).collect();
+        let mixed: Vec<u64> = absent.iter().chain(&present).copied().collect();
+
+        for items in [&present, &absent, &mixed, &Vec::new()] {
+            assert_eq!(bf.contains_any(items), items.iter().any(|i| bf.contains(i)));
+            assert_eq!(bf.contains_all(items), items.iter().all(|i| bf.contains(i)));
+        }
+        assert!(bf.contains_any(&mixed));
+        assert!(!bf.contains_all(&mixed));
+    }
+
+    #[test]
+    fn test_try_insert_fails_near_capacity() {
+        let capacity = 1000;
+        let mut bf = BloomFilter::<u64, AHasher>::new(capacity, 0.01);
+        let mut accepted = 0;
+        let mut refused = None;
+        for i in 0..(capacity as u64 * 2) {
+            if bf.try_insert(&i).is_err() {
+                refused = Some(i);
+                break;
+            }
+            accepted += 1;
+        }
+
+        assert!(
+            (capacity * 9 / 10..=capacity * 11 / 10).contains(&accepted),
+            "Saturated after {} inserts",
+            accepted
+        );
+        assert_eq!(bf.len(), accepted);
+        assert!(bf.current_false_positive_rate() <= bf.false_positive_rate());
+
+        let set_bits = bf.set_bits();
+        assert_eq!(bf.try_insert(&refused.unwrap()), Err(Saturated));
+        assert_eq!(bf.set_bits(), set_bits, "Refused insert must not set bits");
+    }
+
+    #[test]
+    fn test_fill_histogram() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            bf.insert(&i);
+        }
+        let histogram = bf.fill_histogram(10);
+        assert_eq!(histogram.len(), 10);
+        assert_eq!(histogram.iter().sum::<usize>(), bf.set_bits());
+
+        let expected = bf.set_bits() as f64 / 10.0;
+        for count in histogram {
+            assert!((count as f64 - expected).abs() < expected * 0.2);
+        }
+    }
+
+    #[test]
+    fn test_dense_storage_backend() {
+        let mut dense = BloomFilter::<u64, AHasher, DenseBits>::new(1000, 0.01);
+        let mut default = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            dense.insert(&i);
+            default.insert(&i);
+        }
+        for i in 0..2000u64 {
+            assert_eq!(dense.contains(&i), default.contains(&i));
+        }
+        assert_eq!(dense.as_bytes(), default.as_bytes());
+    }
+
+    #[test]
+    fn test_serialized_size_matches_as_bytes() {
+        for (capacity, fpr) in [(1, 0.5), (100, 0.01), (1000, 0.001), (12_345, 0.05)] {
+            let mut bf = BloomFilter::<_, AHasher>::new(capacity, fpr);
+            for i in 0..capacity as u64 {
+                bf.insert(&i);
+            }
+            assert_eq!(bf.serialized_size(), bf.as_bytes().len());
+        }
+    }
+
+    #[test]
+    fn test_default_seeds_decorrelate_base_hashes() {
+        let bf = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        let mut rng = rand::rng();
+        let samples = 10_000;
+        let differing: u32 = (0..samples)
+            .map(|_| {
+                let (h1, h2) = bf.hashers.hash_item(&rng.random::<u64>());
+                (h1 ^ h2).count_ones()
+            })
+            .sum();
+        let mean = differing as f64 / samples as f64;
+        // Binomial(64, 1/2): mean 32, standard error of the mean ~0.04
+        assert!((mean - 32.0).abs() < 0.5, "mean differing bits {:.2}", mean);
+    }
+
+    #[test]
+    fn test_from_bytes_checks_version_and_hasher() {
+        let mut bf = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        bf.insert(&1);
+        let mut bytes = bf.as_bytes();
+        assert_eq!(
+            BloomFilter::<u64, AHasher>::from_bytes(&bytes).err(),
+            Some(DecodeError::HasherMismatch)
+        );
+        bytes[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert_eq!(
+            BloomFilter::<u64, XXHasher>::from_bytes(&bytes).err(),
+            Some(DecodeError::UnsupportedVersion)
+        );
+
+        // Boxed hashers decode through `with_seed`, i.e. as XXHasher
+        type Dyn = BloomFilter<u64, Box<dyn DynHasher64>>;
+        let decoded = Dyn::from_bytes(&bf.as_bytes()).unwrap();
+        assert_eq!(decoded.hasher_id(), "xxhash3");
+        assert!(decoded.contains(&1));
+        let mut ahash = Dyn::with_hasher_instance(1000, 0.01, Box::new(AHasher::with_seed(0)));
+        ahash.rehash_with_seed(3, [1u64].into_iter());
+        assert_eq!(
+            Dyn::from_bytes(&ahash.as_bytes()).err(),
+            Some(DecodeError::HasherMismatch)
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_instance_hashers() {
+        let mut bf = BloomFilter::<u64, AHasher>::with_hasher_instance(1000, 0.01, AHasher::new());
+        bf.insert(&1);
+        assert_eq!(
+            BloomFilter::<u64, AHasher>::from_bytes(&bf.as_bytes()).err(),
+            Some(DecodeError::InstanceHasher)
+        );
+
+        bf.rehash_with_seed(4, [1u64].into_iter());
+        let decoded = BloomFilter::<u64, AHasher>::from_bytes(&bf.as_bytes()).unwrap();
+        assert!(decoded.contains(&1));
+    }
+
+    #[test]
+    fn test_seed_offsets_are_kept_and_serialized() {
+        let mut bf = BloomFilter::<u64, XXHasher>::with_seed_offsets(1000, 0.01, [3, 8]);
+        assert_eq!(bf.base_seeds(), [3, 8]);
+        bf.rehash_with_seed(10, 0..500u64);
+        assert_eq!(bf.base_seeds(), [13, 18]);
+
+        let decoded = BloomFilter::<u64, XXHasher>::from_bytes(&bf.as_bytes()).unwrap();
+        assert_eq!(decoded.seed_offsets(), [3, 8]);
+        assert!((0..500u64).all(|i| decoded.contains(&i)));
+
+        let mut default = BloomFilter::<u64, XXHasher>::new(1000, 0.01);
+        default.rehash_with_seed(10, std::iter::empty());
+        assert_eq!(
+            bf.intersect_into(&default).err(),
+            Some(MergeError::SeedMismatch)
+        );
+    }
+
+    #[test]
+    fn test_rebuild_retaining() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            bf.insert(&i);
+        }
+
+        let rebuilt = bf.rebuild_retaining(0..10_000u64, |key| key % 10 == 0);
+        assert_eq!(rebuilt.len(), 1000);
+        assert_eq!(rebuilt.capacity(), 1000);
+        assert!(rebuilt.num_bits() < bf.num_bits() / 5);
+        assert_eq!(rebuilt.base_seeds(), bf.base_seeds());
+        assert!((0..10_000u64).step_by(10).all(|key| rebuilt.contains(&key)));
+        let dropped = (0..10_000u64)
+            .filter(|key| key % 10 != 0 && rebuilt.contains(key))
+            .count();
+        assert!(
+            dropped < 150,
+            "{} of 9000 dropped keys still present",
+            dropped
+        );
+
+        let empty = bf.rebuild_retaining(0..10_000u64, |_| false);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "debug-invariants"))]
+    #[should_panic(expected = "False negative")]
+    fn test_invariant_check_catches_cleared_bit() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            bf.insert(&i);
+        }
+        assert!((0..1000u64).all(|i| bf.contains(&i)));
+
+        let pos = positions(&bf, &7).next().unwrap();
+        bf.bit_array.set(pos, false);
+        bf.contains(&7);
+    }
+
+    #[test]
+    fn test_rehash_with_seed_preserves_membership() {
+        let mut bf = BloomFilter::<_, AHasher>::new(1000, 0.01);
+        let keys: Vec<u64> = (0..1000).collect();
+        for key in &keys {
+            bf.insert(key);
+        }
+        let bits_before = bf.bit_array.clone();
+
+        bf.rehash_with_seed(0xDEADBEEF, keys.iter().copied());
+
+        assert_eq!(bf.seed(), 0xDEADBEEF);
+        assert_eq!(bf.len(), keys.len());
+        assert_ne!(
+            bf.bit_array, bits_before,
+            "New seed should remap keys to new bits"
+        );
+        for key in &keys {
+            assert!(bf.contains(key), "False negative after rehash for {}", key);
+        }
+    }
 }
@@ -0,0 +1,178 @@
+use bit_vec::BitVec;
+
+/// Backing bit array for a Bloom filter.
+///
+/// Separating storage from the filter logic lets dense, packed, memory-mapped or atomic
+/// layouts share one filter implementation.
+pub trait BitStorage {
+    /// Creates a storage holding `len` bits, all unset.
+    fn with_len(len: usize) -> Self
+    where
+        Self: Sized;
+
+    fn get(&self, index: usize) -> bool;
+
+    fn set(&mut self, index: usize, value: bool);
+
+    /// Number of addressable bits.
+    fn len(&self) -> usize;
+
+    /// Number of bits of backing memory, i.e. [`len`](Self::len) rounded up to the storage's
+    /// word size. The bits past `len` are never addressed.
+    fn allocated_bits(&self) -> usize {
+        self.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of bits currently set.
+    fn count_ones(&self) -> usize;
+
+    /// Unsets every bit, keeping the length.
+    fn clear(&mut self);
+
+    /// Packs the bits into `len().div_ceil(8)` bytes, most significant bit first.
+    fn as_bytes(&self) -> Vec<u8>;
+}
+
+impl BitStorage for BitVec {
+    fn with_len(len: usize) -> Self {
+        BitVec::from_elem(len, false)
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self[index]
+    }
+
+    fn set(&mut self, index: usize, value: bool) {
+        BitVec::set(self, index, value);
+    }
+
+    fn len(&self) -> usize {
+        BitVec::len(self)
+    }
+
+    fn allocated_bits(&self) -> usize {
+        self.storage().len() * u32::BITS as usize
+    }
+
+    fn count_ones(&self) -> usize {
+        BitVec::count_ones(self) as usize
+    }
+
+    fn clear(&mut self) {
+        BitVec::clear(self);
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+/// A dense bit array packed into 64-bit words.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DenseBits {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitStorage for DenseBits {
+    fn with_len(len: usize) -> Self {
+        DenseBits {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "Bit index {} out of bounds", index);
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "Bit index {} out of bounds", index);
+        let mask = 1 << (index % 64);
+        if value {
+            self.words[index / 64] |= mask;
+        } else {
+            self.words[index / 64] &= !mask;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn allocated_bits(&self) -> usize {
+        self.words.len() * u64::BITS as usize
+    }
+
+    fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        (0..self.len.div_ceil(8))
+            .map(|byte| {
+                (0..8)
+                    .filter(|bit| {
+                        let index = byte * 8 + bit;
+                        index < self.len && self.get(index)
+                    })
+                    .fold(0u8, |acc, bit| acc | (0x80 >> bit))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise<S: BitStorage>() {
+        let mut bits = S::with_len(130);
+        assert_eq!(bits.len(), 130);
+        assert_eq!(bits.count_ones(), 0);
+
+        for i in [0, 7, 8, 63, 64, 129] {
+            bits.set(i, true);
+        }
+        assert!(bits.get(63) && bits.get(64) && !bits.get(65));
+        assert_eq!(bits.count_ones(), 6);
+
+        bits.set(7, false);
+        assert!(!bits.get(7));
+
+        bits.clear();
+        assert_eq!(bits.count_ones(), 0);
+        assert_eq!(bits.len(), 130);
+        assert!(bits.allocated_bits() >= 130);
+    }
+
+    #[test]
+    fn test_bitvec_storage() {
+        exercise::<BitVec>();
+    }
+
+    #[test]
+    fn test_dense_storage() {
+        exercise::<DenseBits>();
+    }
+
+    #[test]
+    fn test_as_bytes_matches_between_backends() {
+        let mut bitvec = BitVec::with_len(77);
+        let mut dense = DenseBits::with_len(77);
+        for i in (0..77).step_by(3) {
+            BitStorage::set(&mut bitvec, i, true);
+            dense.set(i, true);
+        }
+        assert_eq!(BitStorage::as_bytes(&bitvec), dense.as_bytes());
+    }
+}
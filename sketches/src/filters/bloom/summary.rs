@@ -0,0 +1,198 @@
+use super::BloomFilter;
+use super::storage::BitStorage;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::hash::Hash;
+
+/// Snapshot of a Bloom filter's sizing and fill metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomSummary {
+    /// Number of bits (`m`)
+    pub num_bits: usize,
+    /// Number of hash functions (`k`)
+    pub num_hashes: usize,
+    /// Number of bits currently set
+    pub set_bits: usize,
+    /// Fraction of bits currently set
+    pub fill_ratio: f64,
+    /// Number of insertions, including duplicates
+    pub len: usize,
+    /// Number of items the filter was sized for
+    pub capacity: usize,
+    /// Configured false positive rate
+    pub false_positive_rate: f64,
+    /// False positive rate implied by the current fill
+    pub current_false_positive_rate: f64,
+    /// Distinct items estimated from the fill
+    pub estimated_distinct: f64,
+}
+
+impl<T: Hash, H: Hasher64, S: BitStorage> BloomFilter<T, H, S> {
+    /// Collects all key metrics of the filter in one call
+    pub fn summary(&self) -> BloomSummary {
+        BloomSummary {
+            num_bits: self.num_bits(),
+            num_hashes: self.num_hashes(),
+            set_bits: self.set_bits(),
+            fill_ratio: self.fill_ratio(),
+            len: self.len(),
+            capacity: self.capacity(),
+            false_positive_rate: self.false_positive_rate(),
+            current_false_positive_rate: self.current_false_positive_rate(),
+            estimated_distinct: self.estimated_distinct(),
+        }
+    }
+
+    /// Renders the filter's health as Prometheus text-format gauges, for a `/metrics` endpoint
+    ///
+    /// Emits `<name>_fill_ratio`, `<name>_current_fpr`, `<name>_insertions` and
+    /// `<name>_estimated_distinct`, each with `# HELP` and `# TYPE` lines. `name` must be a
+    /// valid metric name (`[a-zA-Z_:][a-zA-Z0-9_:]*`).
+    pub fn metrics(&self, name: &str) -> String {
+        assert!(is_metric_name(name), "Invalid metric name: {:?}", name);
+        let summary = self.summary();
+        let gauges = [
+            ("fill_ratio", "Fraction of bits set", summary.fill_ratio),
+            (
+                "current_fpr",
+                "False positive rate implied by the current fill",
+                summary.current_false_positive_rate,
+            ),
+            (
+                "insertions",
+                "Number of insertions, including duplicates",
+                summary.len as f64,
+            ),
+            (
+                "estimated_distinct",
+                "Distinct items estimated from the fill",
+                summary.estimated_distinct,
+            ),
+        ];
+        let mut out = String::new();
+        for (metric, help, value) in gauges {
+            out.push_str(&format!("# HELP {name}_{metric} {help}\n"));
+            out.push_str(&format!("# TYPE {name}_{metric} gauge\n"));
+            out.push_str(&format!("{name}_{metric} {}\n", metric_value(value)));
+        }
+        out
+    }
+}
+
+fn is_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+/// Formats a sample value as the exposition format expects, which spells infinity `+Inf`.
+fn metric_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+impl std::fmt::Display for BloomSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Bloom Filter Summary:\n  Bits: {} ({} set, {:.1}% full)\n  Hashes: {}\n  Items: {} inserted, ~{:.0} distinct, capacity {}\n  FPR: {:.4} configured, {:.4} current",
+            self.num_bits,
+            self.set_bits,
+            self.fill_ratio * 100.0,
+            self.num_hashes,
+            self.len,
+            self.estimated_distinct,
+            self.capacity,
+            self.false_positive_rate,
+            self.current_false_positive_rate
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_summary_matches_accessors() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..600u64 {
+            bf.insert(&i);
+        }
+        let summary = bf.summary();
+
+        assert_eq!(summary.num_bits, bf.num_bits());
+        assert_eq!(summary.num_hashes, bf.num_hashes());
+        assert_eq!(summary.set_bits, bf.set_bits());
+        assert_eq!(summary.fill_ratio, bf.fill_ratio());
+        assert_eq!(summary.len, bf.len());
+        assert_eq!(summary.capacity, bf.capacity());
+        assert_eq!(summary.false_positive_rate, bf.false_positive_rate());
+        assert_eq!(
+            summary.current_false_positive_rate,
+            bf.current_false_positive_rate()
+        );
+        assert_eq!(summary.estimated_distinct, bf.estimated_distinct());
+
+        let shown = summary.to_string();
+        assert!(shown.contains(&format!("Bits: {} ({} set", bf.num_bits(), bf.set_bits())));
+        assert!(shown.contains(&format!("Hashes: {}\n", bf.num_hashes())));
+        assert!(shown.contains(&format!(
+            "FPR: {:.4} configured, {:.4} current",
+            bf.false_positive_rate(),
+            bf.current_false_positive_rate()
+        )));
+    }
+
+    #[test]
+    fn test_metrics_exposition() {
+        let mut bf = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..600u64 {
+            bf.insert(&i);
+        }
+
+        let text = bf.metrics("sessions_bloom");
+        let mut samples = Vec::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                assert!(matches!(parts.next(), Some("HELP" | "TYPE")), "{}", line);
+                assert!(
+                    parts
+                        .next()
+                        .is_some_and(|name| name.starts_with("sessions_bloom_"))
+                );
+                continue;
+            }
+            let (name, value) = line.split_once(' ').expect("sample line");
+            assert!(is_metric_name(name), "{}", line);
+            samples.push((name, value.parse::<f64>().expect("numeric sample")));
+        }
+        assert!(text.ends_with('\n'));
+        assert_eq!(
+            samples,
+            vec![
+                ("sessions_bloom_fill_ratio", bf.fill_ratio()),
+                (
+                    "sessions_bloom_current_fpr",
+                    bf.current_false_positive_rate()
+                ),
+                ("sessions_bloom_insertions", 600.0),
+                ("sessions_bloom_estimated_distinct", bf.estimated_distinct()),
+            ]
+        );
+        assert_eq!(text.matches(" gauge\n").count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid metric name")]
+    fn test_metrics_rejects_invalid_name() {
+        BloomFilter::<u64, AHasher>::new(10, 0.01).metrics("bloom-filter");
+    }
+}
@@ -0,0 +1,179 @@
+use super::BloomFilter;
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// A ring of per-window Bloom filters giving approximate membership over the most recent windows.
+///
+/// Windows are identified by an externally supplied, non-decreasing id (e.g. a log offset divided
+/// by a window size). Only the last `num_windows` window ids are retained: starting a window
+/// drops every window that falls out of that range, so memory stays bounded over an unbounded
+/// stream and items expire after roughly `num_windows` windows.
+pub struct WindowedBloomFilter<T, H: Hasher64> {
+    windows: VecDeque<(u64, BloomFilter<T, H>)>, // Oldest first
+    num_windows: usize,
+    window_capacity: usize,
+    false_positive_rate: f64,
+}
+
+impl<T: Hash, H: Hasher64> WindowedBloomFilter<T, H> {
+    /// Creates a filter retaining `num_windows` windows, each sized for `window_capacity` items
+    /// at `false_positive_rate`.
+    ///
+    /// A query checks every live window, so the combined false positive rate is up to
+    /// `num_windows` times the per-window rate.
+    pub fn new(num_windows: usize, window_capacity: usize, false_positive_rate: f64) -> Self {
+        assert!(num_windows > 0, "Number of windows must be greater than 0");
+        WindowedBloomFilter {
+            windows: VecDeque::with_capacity(num_windows),
+            num_windows,
+            window_capacity,
+            false_positive_rate,
+        }
+    }
+
+    /// Inserts `item` into window `window`, starting that window if it is new.
+    ///
+    /// Returns `false` (and stores nothing) if `window` is older than the newest window and is
+    /// not live, e.g. because it has already been evicted.
+    pub fn insert(&mut self, window: u64, item: &T) -> bool {
+        if self
+            .windows
+            .back()
+            .is_none_or(|(newest, _)| window > *newest)
+        {
+            self.start_window(window);
+        }
+        match self.windows.iter_mut().find(|(id, _)| *id == window) {
+            Some((_, filter)) => {
+                filter.insert(item);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether `item` was (probably) inserted in any live window.
+    pub fn contains(&self, item: &T) -> bool {
+        self.windows.iter().any(|(_, filter)| filter.contains(item))
+    }
+
+    /// Checks whether `item` was (probably) inserted in window `window`.
+    pub fn contains_in_window(&self, window: u64, item: &T) -> bool {
+        self.windows
+            .iter()
+            .any(|(id, filter)| *id == window && filter.contains(item))
+    }
+
+    /// Returns the id of the newest window, if any item has been inserted.
+    pub fn current_window(&self) -> Option<u64> {
+        self.windows.back().map(|(id, _)| *id)
+    }
+
+    /// Returns the ids of the live windows, oldest first.
+    pub fn live_windows(&self) -> impl Iterator<Item = u64> + '_ {
+        self.windows.iter().map(|(id, _)| *id)
+    }
+
+    /// Total number of insertions across the live windows.
+    pub fn len(&self) -> usize {
+        self.windows.iter().map(|(_, filter)| filter.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every window, so the next insert may start at any window id.
+    pub fn clear(&mut self) {
+        self.windows.clear();
+    }
+
+    fn start_window(&mut self, window: u64) {
+        let oldest_live = window.saturating_sub(self.num_windows as u64 - 1);
+        while self
+            .windows
+            .front()
+            .is_some_and(|(id, _)| *id < oldest_live)
+        {
+            self.windows.pop_front();
+        }
+        self.windows.push_back((
+            window,
+            BloomFilter::new(self.window_capacity, self.false_positive_rate),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_recent_windows_are_retained() {
+        let mut filter = WindowedBloomFilter::<u64, AHasher>::new(3, 1000, 0.01);
+        for window in 0..3u64 {
+            for i in 0..1000 {
+                assert!(filter.insert(window, &(window * 1000 + i)));
+            }
+        }
+
+        for item in 0..3000u64 {
+            assert!(filter.contains(&item), "False negative for {}", item);
+        }
+        assert_eq!(filter.live_windows().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(filter.len(), 3000);
+    }
+
+    #[test]
+    fn test_evicted_windows_stop_being_reported() {
+        let mut filter = WindowedBloomFilter::<u64, AHasher>::new(3, 1000, 0.01);
+        for i in 0..1000u64 {
+            filter.insert(0, &i);
+        }
+        for window in 1..=3u64 {
+            for i in 0..1000 {
+                filter.insert(window, &(window * 1000 + i));
+            }
+        }
+
+        assert_eq!(filter.current_window(), Some(3));
+        assert_eq!(filter.live_windows().collect::<Vec<_>>(), vec![1, 2, 3]);
+        let still_reported = (0..1000u64).filter(|i| filter.contains(i)).count();
+        assert!(
+            still_reported < 100,
+            "Evicted window still reported {} items",
+            still_reported
+        );
+        for item in 1000..4000u64 {
+            assert!(filter.contains(&item));
+        }
+    }
+
+    #[test]
+    fn test_window_gap_evicts_everything_out_of_range() {
+        let mut filter = WindowedBloomFilter::<u64, AHasher>::new(3, 100, 0.01);
+        filter.insert(0, &1);
+        filter.insert(1, &2);
+        filter.insert(10, &3);
+
+        assert_eq!(filter.live_windows().collect::<Vec<_>>(), vec![10]);
+        assert!(
+            !filter.insert(1, &4),
+            "Evicted window should reject inserts"
+        );
+        assert!(filter.contains_in_window(10, &3));
+    }
+
+    #[test]
+    fn test_insert_into_older_live_window() {
+        let mut filter = WindowedBloomFilter::<u64, AHasher>::new(3, 100, 0.01);
+        filter.insert(5, &1);
+        filter.insert(6, &2);
+        assert!(filter.insert(5, &3));
+        assert!(filter.contains_in_window(5, &3));
+        assert_eq!(filter.current_window(), Some(6));
+    }
+}
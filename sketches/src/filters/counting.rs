@@ -0,0 +1,256 @@
+use crate::filters::bloom::{
+    BaseHashers, BloomFilter, DEFAULT_SEED_OFFSETS, KRounding, double_hash_positions,
+};
+use crate::filters::traits::ApproximateMembershipQuery;
+use crate::hashing::Hasher64;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// A Bloom filter with a counter per position instead of a bit, so items can be removed.
+///
+/// Sized and hashed like a [`BloomFilter`] with the same capacity and rate, down to mapping
+/// every item to the same positions, but each position holds a `u8`: eight times the memory.
+/// Inserting increments an item's `k` counters and removing decrements them; an item is
+/// present while all of its counters are non-zero.
+///
+/// # Saturation
+///
+/// A counter stops at 255. Once saturated it is never decremented again, since its true count
+/// is unknown and decrementing could drop it to zero while items still map to it, causing
+/// false negatives. The position is then permanently set, like a bit in a plain Bloom filter,
+/// and the false positive rate no longer recovers when items are removed.
+/// [`saturated_positions`](Self::saturated_positions) reports how many positions are stuck;
+/// with 8-bit counters this takes hundreds of insertions hitting a position, far past capacity.
+///
+/// # Removal
+///
+/// Only remove items that were inserted. [`remove`](Self::remove) ignores items the filter
+/// reports absent, but an item that is a false positive can't be told apart from an inserted
+/// one: removing it decrements counters other items rely on, and can make them disappear.
+pub struct CountingBloomFilter<T, H: Hasher64> {
+    counters: Vec<u8>,
+    m: usize,
+    k: usize,
+    n: usize,
+    f: f64,
+    count: usize,
+    hashers: BaseHashers<H>,
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: Hash, H: Hasher64> CountingBloomFilter<T, H> {
+    /// Creates a filter sized like `BloomFilter::new(capacity, false_positive_rate)`.
+    ///
+    /// Panics if the capacity is zero or the rate is outside `(0, 1)`, the cases
+    /// [`BloomFilter::try_new`] rejects.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+        let m = BloomFilter::<T, H>::calculate_m(capacity, false_positive_rate)
+            .unwrap_or_else(|err| panic!("Cannot size filter: {}", err));
+        let k = BloomFilter::<T, H>::calculate_k(m, capacity, KRounding::default());
+        CountingBloomFilter {
+            counters: vec![0; m],
+            m,
+            k,
+            n: capacity,
+            f: false_positive_rate,
+            count: 0,
+            hashers: BaseHashers::from_seeds(DEFAULT_SEED_OFFSETS),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Removes one insertion of `item`, returning false (and changing nothing) if the filter
+    /// reports it absent.
+    pub fn remove(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        for pos in self.positions(item) {
+            let counter = &mut self.counters[pos];
+            if *counter < u8::MAX {
+                *counter -= 1;
+            }
+        }
+        self.count = self.count.saturating_sub(1);
+        true
+    }
+
+    /// Number of counters stuck at their maximum, which removals no longer decrement.
+    pub fn saturated_positions(&self) -> usize {
+        self.counters
+            .iter()
+            .filter(|&&counter| counter == u8::MAX)
+            .count()
+    }
+
+    /// Number of positions (`m`).
+    pub fn num_counters(&self) -> usize {
+        self.m
+    }
+
+    /// Number of hash functions (`k`).
+    pub fn num_hashes(&self) -> usize {
+        self.k
+    }
+
+    fn positions(&self, item: &T) -> impl Iterator<Item = usize> + use<T, H> {
+        let (h1, h2) = self.hashers.hash_item(item);
+        double_hash_positions(h1, h2, self.k, self.m)
+    }
+}
+
+impl<T: Hash, H: Hasher64> ApproximateMembershipQuery<T> for CountingBloomFilter<T, H> {
+    fn insert(&mut self, item: &T) {
+        for pos in self.positions(item) {
+            let counter = &mut self.counters[pos];
+            *counter = counter.saturating_add(1);
+        }
+        self.count += 1;
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        self.positions(item).all(|pos| self.counters[pos] > 0)
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        self.f
+    }
+
+    /// `(1 - e^(-k * n / m))^k` for the `n` insertions not yet removed, as for a
+    /// [`BloomFilter`] holding the same items. Saturated counters that removals could no longer
+    /// clear keep the real rate a little higher.
+    fn theoretical_false_positive_rate(&self) -> f64 {
+        let (m, k) = (self.m as f64, self.k as f64);
+        (1.0 - (-k * self.count as f64 / m).exp()).powf(k)
+    }
+
+    fn capacity(&self) -> usize {
+        self.n
+    }
+
+    /// Number of insertions not yet removed.
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn clear(&mut self) {
+        self.counters.fill(0);
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::bloom::HEADER_LEN;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_insert_remove_round_trip() {
+        let mut filter = CountingBloomFilter::<u64, AHasher>::new(10_000, 0.01);
+        for i in 0..10_000u64 {
+            filter.insert(&i);
+        }
+        for i in 0..5000u64 {
+            assert!(filter.remove(&i));
+        }
+        assert_eq!(filter.len(), 5000);
+
+        assert!((5000..10_000u64).all(|i| filter.contains(&i)));
+        let remaining = (0..5000u64).filter(|i| filter.contains(i)).count();
+        assert!(remaining < 50, "{} removed items still present", remaining);
+
+        for i in 5000..10_000u64 {
+            filter.remove(&i);
+        }
+        assert!(filter.is_empty());
+        assert!(filter.counters.iter().all(|&counter| counter == 0));
+    }
+
+    #[test]
+    fn test_removing_absent_items_keeps_others() {
+        let mut filter = CountingBloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            filter.insert(&i);
+        }
+        let absent: Vec<u64> = (1000..11_000u64).filter(|i| !filter.contains(i)).collect();
+        assert!(absent.len() > 9800);
+        for item in &absent {
+            assert!(!filter.remove(item));
+        }
+        assert_eq!(filter.len(), 1000);
+        assert!((0..1000u64).all(|i| filter.contains(&i)));
+    }
+
+    #[test]
+    fn test_saturated_counters_stick() {
+        let mut filter = CountingBloomFilter::<u64, AHasher>::new(100, 0.01);
+        for _ in 0..300 {
+            filter.insert(&7);
+        }
+        assert_eq!(filter.saturated_positions(), filter.num_hashes());
+        for _ in 0..300 {
+            filter.remove(&7);
+        }
+        assert!(
+            filter.contains(&7),
+            "Saturated counters must not be decremented"
+        );
+        assert_eq!(filter.saturated_positions(), filter.num_hashes());
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_positions_match_bloom_filter() {
+        let mut filter = CountingBloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let mut bloom = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            filter.insert(&i);
+            bloom.insert(&i);
+        }
+        let bits = &bloom.as_bytes()[HEADER_LEN..];
+        assert_eq!(filter.num_counters(), bloom.num_bits());
+        for (pos, &counter) in filter.counters.iter().enumerate() {
+            let bit = bits[pos / 8] & (0x80 >> (pos % 8)) != 0;
+            assert_eq!(counter > 0, bit, "Position {}", pos);
+        }
+    }
+
+    #[test]
+    fn test_theoretical_false_positive_rate_tracks_removals() {
+        let mut filter = CountingBloomFilter::<u64, AHasher>::new(1000, 0.01);
+        let bloom = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        assert_eq!(filter.theoretical_false_positive_rate(), 0.0);
+        for i in 0..1000u64 {
+            filter.insert(&i);
+        }
+        let full = filter.theoretical_false_positive_rate();
+        assert_eq!(full, bloom.fpr_at_load(1000));
+        assert!((full - 0.01).abs() < 0.002, "rate {}", full);
+
+        for i in 0..500u64 {
+            filter.remove(&i);
+        }
+        assert_eq!(
+            filter.theoretical_false_positive_rate(),
+            bloom.fpr_at_load(500)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot size filter: capacity must be positive and the rate within (0, 1)"
+    )]
+    fn test_rate_of_one_panics() {
+        let _ = CountingBloomFilter::<u64, AHasher>::new(1000, 1.0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot size filter: capacity must be positive and the rate within (0, 1)"
+    )]
+    fn test_zero_rate_panics() {
+        let _ = CountingBloomFilter::<u64, AHasher>::new(1000, 0.0);
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+/// Returned by a guarded insert when accepting the item would push the filter's false positive
+/// rate past its configured target. The filter is left unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Saturated;
+
+impl fmt::Display for Saturated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "filter is saturated: inserting would exceed the configured false positive rate"
+        )
+    }
+}
+
+impl std::error::Error for Saturated {}
+
+/// Returned when two filters can't be combined bit-for-bit because they don't map items to the
+/// same positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeError {
+    /// The filters have a different number of bits or hash functions.
+    ShapeMismatch,
+    /// The filters have the same shape but were configured for different false positive
+    /// rates, so the result's [`false_positive_rate`] would be wrong for one of them.
+    ///
+    /// [`false_positive_rate`]: crate::filters::traits::ApproximateMembershipQuery::false_positive_rate
+    RateMismatch,
+    /// The filters hash with different seeds.
+    SeedMismatch,
+    /// The filters hash with different algorithms (see
+    /// [`DynHasher64`](crate::hashing::DynHasher64)).
+    HasherMismatch,
+    /// No filters were given to combine.
+    NoFilters,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::ShapeMismatch => {
+                write!(f, "filters differ in number of bits or hash functions")
+            }
+            MergeError::RateMismatch => {
+                write!(f, "filters target different false positive rates")
+            }
+            MergeError::SeedMismatch => write!(f, "filters use different hash seeds"),
+            MergeError::HasherMismatch => write!(f, "filters use different hash algorithms"),
+            MergeError::NoFilters => write!(f, "no filters to combine"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Returned when a filter can't be sized for the requested capacity and false positive rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizingError {
+    /// The capacity is zero or the false positive rate is outside `(0, 1)`.
+    InvalidParameters,
+    /// The filter would need more bits than can be addressed (see
+    /// [`MAX_BITS`](crate::filters::bloom::MAX_BITS)).
+    TooManyBits,
+}
+
+impl fmt::Display for SizingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizingError::InvalidParameters => {
+                write!(f, "capacity must be positive and the rate within (0, 1)")
+            }
+            SizingError::TooManyBits => write!(f, "filter would need too many bits"),
+        }
+    }
+}
+
+impl std::error::Error for SizingError {}
+
+/// Returned when bytes can't be decoded into a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input is shorter than the header or doesn't start with the expected magic bytes.
+    BadHeader,
+    /// The header declares impossible parameters (e.g. zero bits or a rate outside `(0, 1)`).
+    InvalidParameters,
+    /// The bit array's length doesn't match the number of bits the header declares.
+    LengthMismatch,
+    /// The input was written by another version of the serialization format.
+    UnsupportedVersion,
+    /// The filter was built with a different hash algorithm than the one decoding it.
+    HasherMismatch,
+    /// The filter was built from a hasher instance, which isn't serialized, so its hashers
+    /// can't be rebuilt.
+    InstanceHasher,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadHeader => write!(f, "missing or malformed filter header"),
+            DecodeError::InvalidParameters => write!(f, "header declares invalid parameters"),
+            DecodeError::LengthMismatch => write!(f, "bit array doesn't match the header"),
+            DecodeError::UnsupportedVersion => write!(f, "unsupported serialization format"),
+            DecodeError::HasherMismatch => write!(f, "filter uses a different hash algorithm"),
+            DecodeError::InstanceHasher => {
+                write!(f, "filter hashes with an instance that isn't serialized")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for std::io::Error {
+    fn from(err: DecodeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
@@ -0,0 +1,93 @@
+use crate::filters::traits::ApproximateMembershipQuery;
+use std::hash::Hash;
+
+/// An append-only, in-memory log of the keys inserted into a filter.
+///
+/// Bloom filters can't enumerate their contents, so resizing, rehashing under a new seed or
+/// compacting a filter requires the original keys. Routing inserts through a `KeyLog` keeps
+/// them available so the filter can be rebuilt at any time.
+#[derive(Clone, Debug, Default)]
+pub struct KeyLog<T> {
+    keys: Vec<T>,
+}
+
+impl<T: Hash> KeyLog<T> {
+    pub fn new() -> Self {
+        KeyLog { keys: Vec::new() }
+    }
+
+    /// Appends `item` to the log without touching any filter.
+    pub fn record(&mut self, item: T) {
+        self.keys.push(item);
+    }
+
+    /// Inserts `item` into `filter` and appends it to the log.
+    pub fn insert_into<F: ApproximateMembershipQuery<T>>(&mut self, filter: &mut F, item: T) {
+        filter.insert(&item);
+        self.record(item);
+    }
+
+    /// Re-inserts every logged key, in insertion order, into `filter`.
+    pub fn replay_into<F: ApproximateMembershipQuery<T>>(&self, filter: &mut F) {
+        for key in &self.keys {
+            filter.insert(key);
+        }
+    }
+
+    /// Iterates over the logged keys in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.keys.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::bloom::BloomFilter;
+    use crate::hashing::AHasher;
+
+    #[test]
+    fn test_replay_reproduces_membership() {
+        let mut log = KeyLog::new();
+        let mut original = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        for i in 0..1000u64 {
+            log.insert_into(&mut original, i * 7);
+        }
+        assert_eq!(log.len(), 1000);
+
+        let mut rebuilt = BloomFilter::<u64, AHasher>::new(1000, 0.01);
+        log.replay_into(&mut rebuilt);
+
+        assert_eq!(rebuilt.len(), original.len());
+        assert_eq!(rebuilt.as_bytes(), original.as_bytes());
+        for key in log.iter() {
+            assert!(rebuilt.contains(key), "False negative for {}", key);
+        }
+    }
+
+    #[test]
+    fn test_replay_drives_seed_rotation() {
+        let mut log = KeyLog::new();
+        let mut filter = BloomFilter::<u64, AHasher>::new(500, 0.01);
+        for i in 0..500u64 {
+            log.insert_into(&mut filter, i);
+        }
+
+        filter.rehash_with_seed(42, log.iter().copied());
+        for key in log.iter() {
+            assert!(filter.contains(key));
+        }
+    }
+}
@@ -1,2 +1,5 @@
 pub mod bloom;
+pub mod counting;
+pub mod error;
+pub mod keylog;
 pub mod traits;
@@ -4,9 +4,34 @@ pub trait ApproximateMembershipQuery<T: Hash> {
     fn insert(&mut self, item: &T);
     fn contains(&self, item: &T) -> bool;
     fn false_positive_rate(&self) -> f64;
+
+    /// False positive rate the filter's actual dimensions give at its capacity.
+    ///
+    /// [`false_positive_rate`](Self::false_positive_rate) is the configured target; sizing
+    /// rounds it to integer dimensions, so the achieved rate can be slightly off. The default
+    /// returns the target, for filters whose sizing is exact or not modeled.
+    fn theoretical_false_positive_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
     fn capacity(&self) -> usize;
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Removes every item, keeping the configured capacity and false positive rate, so that
+    /// [`is_empty`](Self::is_empty) holds afterwards.
+    ///
+    /// The default does nothing; every filter in this crate overrides it.
+    fn clear(&mut self) {}
+
+    /// Returns true if any of `items` is (probably) present, stopping at the first hit.
+    fn contains_any(&self, items: &[T]) -> bool {
+        items.iter().any(|item| self.contains(item))
+    }
+
+    /// Returns true if all of `items` are (probably) present, stopping at the first miss.
+    fn contains_all(&self, items: &[T]) -> bool {
+        items.iter().all(|item| self.contains(item))
+    }
 }
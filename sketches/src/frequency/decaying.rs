@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Largest weight an offer may carry before scores are rescaled, keeping them finite.
+const MAX_WEIGHT: f64 = 1e100;
+
+/// The items with the highest exponentially decayed frequency, in memory bounded by `capacity`.
+///
+/// Each occurrence counts `1` when offered and loses half its weight every `half_life`, so an
+/// item seen 100 times in the last minute outranks one seen 1000 times an hour ago once that
+/// hour is a few half-lives: the ranking answers "what is hot now" rather than "what is most
+/// frequent ever". A short half-life reacts quickly to bursts but forgets steady items as fast;
+/// pick it on the order of the window "now" should mean.
+///
+/// At most `capacity` items are tracked. As in Space-Saving, an untracked item offered to a
+/// full structure replaces the item with the lowest score and inherits that score, so scores
+/// can only overestimate and an item that becomes frequent is never locked out.
+///
+/// Offers are expected in time order. Rather than decaying every score on each offer, later
+/// offers carry exponentially larger weights, which ranks items identically.
+pub struct DecayingTopK<T> {
+    capacity: usize,
+    half_life: Duration,
+    scores: HashMap<T, f64>,
+    epoch: Option<Instant>, // Time at which an offer weighs 1
+}
+
+impl<T: Hash + Eq + Clone> DecayingTopK<T> {
+    pub fn new(capacity: usize, half_life: Duration) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+        assert!(!half_life.is_zero(), "Half-life must be greater than 0");
+        DecayingTopK {
+            capacity,
+            half_life,
+            scores: HashMap::with_capacity(capacity),
+            epoch: None,
+        }
+    }
+
+    /// Records one occurrence of `item` at time `now`.
+    pub fn offer(&mut self, item: T, now: Instant) {
+        self.epoch.get_or_insert(now);
+        let mut weight = self.weight(now);
+        if weight > MAX_WEIGHT {
+            self.rescale(weight, now);
+            weight = 1.0;
+        }
+
+        if let Some(score) = self.scores.get_mut(&item) {
+            *score += weight;
+        } else if self.scores.len() < self.capacity {
+            self.scores.insert(item, weight);
+        } else {
+            let (evicted, floor) = self
+                .scores
+                .iter()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(item, &score)| (item.clone(), score))
+                .expect("Capacity is greater than 0");
+            self.scores.remove(&evicted);
+            self.scores.insert(item, floor + weight);
+        }
+    }
+
+    /// The (at most) `k` tracked items with the highest decayed frequency, highest first.
+    pub fn trending(&self, k: usize) -> Vec<T> {
+        let mut ranked: Vec<(&T, f64)> = self.scores.iter().map(|(item, &s)| (item, s)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+            .into_iter()
+            .take(k)
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+
+    /// Decayed frequency of `item` as of `now`, or `None` if it isn't tracked.
+    pub fn score(&self, item: &T, now: Instant) -> Option<f64> {
+        let score = self.scores.get(item)?;
+        Some(score / self.weight(now))
+    }
+
+    pub fn half_life(&self) -> Duration {
+        self.half_life
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Weight of an occurrence at `now`, relative to the epoch.
+    fn weight(&self, now: Instant) -> f64 {
+        let Some(epoch) = self.epoch else {
+            return 1.0;
+        };
+        let half_lives =
+            now.saturating_duration_since(epoch).as_secs_f64() / self.half_life.as_secs_f64();
+        half_lives.exp2()
+    }
+
+    /// Moves the epoch to `now`, dividing every score by the weight of an offer at `now`.
+    fn rescale(&mut self, weight: f64, now: Instant) {
+        for score in self.scores.values_mut() {
+            *score /= weight;
+        }
+        self.epoch = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_burst_overtakes_stale_item() {
+        let half_life = Duration::from_secs(60);
+        let mut top = DecayingTopK::new(10, half_life);
+        let start = Instant::now();
+        for _ in 0..1000 {
+            top.offer("stale", start);
+        }
+        top.offer("steady", start);
+        assert_eq!(top.trending(1), vec!["stale"]);
+
+        // Ten half-lives later the stale item counts for under 1
+        let later = start + 10 * half_life;
+        for _ in 0..100 {
+            top.offer("burst", later);
+        }
+        assert_eq!(top.trending(2), vec!["burst", "stale"]);
+        let stale = top.score(&"stale", later).unwrap();
+        assert!(
+            (stale - 1000.0 / 1024.0).abs() < 1e-9,
+            "stale score {}",
+            stale
+        );
+        assert!((top.score(&"burst", later).unwrap() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capacity_evicts_lowest_score() {
+        let mut top = DecayingTopK::new(2, Duration::from_secs(1));
+        let now = Instant::now();
+        for _ in 0..5 {
+            top.offer(1, now);
+        }
+        top.offer(2, now);
+        top.offer(3, now);
+
+        assert_eq!(top.trending(3), vec![1, 3]);
+        assert_eq!(top.score(&2, now), None);
+        // The newcomer inherits the evicted score, so it may overestimate
+        assert_eq!(top.score(&3, now), Some(2.0));
+    }
+
+    #[test]
+    fn test_scores_stay_finite_over_many_half_lives() {
+        let half_life = Duration::from_millis(1);
+        let mut top = DecayingTopK::new(4, half_life);
+        let start = Instant::now();
+        for step in 0..5000u32 {
+            top.offer(step % 3, start + step * half_life);
+        }
+        let end = start + 4999 * half_life;
+        assert_eq!(top.trending(1), vec![4999 % 3]);
+        assert!((0..3).all(|item| top.score(&item, end).unwrap().is_finite()));
+    }
+}
@@ -0,0 +1,9 @@
+mod ams;
+mod decaying;
+mod rare;
+mod tinylfu;
+
+pub use ams::AmsSketch;
+pub use decaying::DecayingTopK;
+pub use rare::RareItemDetector;
+pub use tinylfu::FrequencySketch;
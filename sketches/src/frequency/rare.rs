@@ -0,0 +1,138 @@
+use crate::hashing::{Hasher64, item_bytes, row_seeds};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Finds rarely seen items among a set of candidates, in memory independent of the number of
+/// distinct items.
+///
+/// Occurrences are counted in a Count-Min sketch: `depth` rows of `width` counters, each item
+/// incrementing one counter per row. The estimate is the smallest of an item's counters.
+///
+/// Colliding items share counters, so an estimate is never below the item's true count. This
+/// makes [`rare`](Self::rare) one-sided: an item it flags was seen at most `max_count` times,
+/// always. The converse does not hold: a rare item sharing counters with frequent ones in
+/// every row can be overestimated and missed. Make `width` a few times the number of distinct
+/// items to keep that rare.
+pub struct RareItemDetector<T, H: Hasher64> {
+    counters: Vec<u64>,
+    width: usize,
+    depth: usize,
+    row_hashers: Vec<H>,
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: Hash, H: Hasher64> RareItemDetector<T, H> {
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0, "Width must be greater than 0");
+        assert!(depth > 0, "Depth must be greater than 0");
+        RareItemDetector {
+            counters: vec![0; width * depth],
+            width,
+            depth,
+            row_hashers: row_seeds(depth).map(H::with_seed).collect(),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Records one occurrence of `item`.
+    pub fn observe(&mut self, item: &T) {
+        let cells: Vec<usize> = self.cells(item).collect();
+        for cell in cells {
+            self.counters[cell] = self.counters[cell].saturating_add(1);
+        }
+    }
+
+    /// Estimated number of occurrences of `item`; never less than the true count.
+    pub fn estimate(&self, item: &T) -> u64 {
+        self.cells(item)
+            .map(|cell| self.counters[cell])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// The candidates estimated to have been seen at most `max_count` times, in input order.
+    ///
+    /// Every returned item was truly seen at most `max_count` times.
+    pub fn rare<'a, I>(&self, candidates: I, max_count: u64) -> Vec<&'a T>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        candidates
+            .into_iter()
+            .filter(|item| self.estimate(item) <= max_count)
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Indices of the counters `item` maps to, one per row.
+    fn cells(&self, item: &T) -> impl Iterator<Item = usize> + use<'_, T, H> {
+        let bytes = item_bytes(item);
+        self.row_hashers
+            .iter()
+            .enumerate()
+            .map(move |(row, hasher)| {
+                row * self.width + (hasher.hash(&bytes) % self.width as u64) as usize
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::XXHasher;
+
+    #[test]
+    fn test_flags_rare_items_only() {
+        let mut detector = RareItemDetector::<u64, XXHasher>::new(2048, 4);
+        // Items 0..100 are seen 1000 times each, items 100..200 at most 3 times
+        for item in 0..100u64 {
+            for _ in 0..1000 {
+                detector.observe(&item);
+            }
+        }
+        for item in 100..200u64 {
+            for _ in 0..item % 3 + 1 {
+                detector.observe(&item);
+            }
+        }
+
+        let candidates: Vec<u64> = (0..200).collect();
+        let rare = detector.rare(&candidates, 3);
+        assert!(
+            rare.iter().all(|&&item| item >= 100),
+            "Frequent item flagged"
+        );
+        assert!(
+            rare.len() >= 95,
+            "Only {} of 100 rare items flagged",
+            rare.len()
+        );
+    }
+
+    #[test]
+    fn test_estimates_never_undercount() {
+        // Narrow rows force collisions; estimates may only be inflated
+        let mut detector = RareItemDetector::<u64, XXHasher>::new(16, 2);
+        for item in 0..1000u64 {
+            for _ in 0..item % 5 {
+                detector.observe(&item);
+            }
+        }
+        for item in 0..1000u64 {
+            assert!(detector.estimate(&item) >= item % 5);
+        }
+        assert!(
+            detector
+                .rare(&(0..1000u64).collect::<Vec<_>>(), 1)
+                .is_empty()
+        );
+    }
+}
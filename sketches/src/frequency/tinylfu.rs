@@ -0,0 +1,156 @@
+use crate::hashing::{Hasher64, item_bytes};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Number of counters each item increments, one per row.
+const DEPTH: usize = 4;
+/// Largest value of a 4-bit counter.
+const MAX_COUNT: u8 = 15;
+/// The low three bits of every 4-bit counter in a word, i.e. each counter halved after a shift.
+const HALVING_MASK: u64 = 0x7777_7777_7777_7777;
+
+/// Approximate recent access frequencies for cache admission, as in TinyLFU (Einziger,
+/// Friedman & Manes).
+///
+/// A Count-Min sketch of 4-bit counters, packed 16 to a word: frequencies saturate at 15,
+/// which is plenty to compare an admission candidate with an eviction victim. An estimate is
+/// the smallest of the item's four counters, so it is never below the true count since the
+/// last reset, but colliding items can inflate it.
+///
+/// # Aging
+///
+/// Once `sample_size` increments have been recorded (10 times the capacity by default), every
+/// counter is halved and the increment tally is halved with it. Frequencies thus reflect
+/// recent history: an item that stops being requested loses half its weight each sample
+/// period, and one burst can't keep an item popular forever. Halving rounds down, so single
+/// accesses are forgotten at the first reset.
+pub struct FrequencySketch<T, H: Hasher64> {
+    table: Vec<u64>,
+    width: usize,
+    additions: usize,
+    sample_size: usize,
+    hasher: H,
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: Hash, H: Hasher64> FrequencySketch<T, H> {
+    /// Creates a sketch for a cache of `capacity` entries, reset every `10 * capacity`
+    /// increments.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_sample_size(capacity, capacity.saturating_mul(10))
+    }
+
+    /// Creates a sketch for a cache of `capacity` entries, reset every `sample_size`
+    /// increments.
+    pub fn with_sample_size(capacity: usize, sample_size: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+        assert!(sample_size > 0, "Sample size must be greater than 0");
+        // At least one word per row; a power of two so rows index with a mask
+        let width = capacity.next_power_of_two().max(16);
+        FrequencySketch {
+            table: vec![0; DEPTH * width / 16],
+            width,
+            additions: 0,
+            sample_size,
+            hasher: H::with_seed(0),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Records one access to `item`, halving every counter if the sample size is reached.
+    pub fn increment(&mut self, item: &T) {
+        let mut incremented = false;
+        for counter in self.counters(item) {
+            if self.get(counter) < MAX_COUNT {
+                self.table[counter / 16] += 1 << (counter % 16 * 4);
+                incremented = true;
+            }
+        }
+        if incremented {
+            self.additions += 1;
+            if self.additions >= self.sample_size {
+                self.reset();
+            }
+        }
+    }
+
+    /// Estimated number of recent accesses to `item`, at most 15.
+    pub fn frequency(&self, item: &T) -> u8 {
+        self.counters(item)
+            .map(|counter| self.get(counter))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Halves every counter and the increment tally; done automatically every `sample_size`
+    /// increments.
+    pub fn reset(&mut self) {
+        for word in &mut self.table {
+            *word = (*word >> 1) & HALVING_MASK;
+        }
+        self.additions /= 2;
+    }
+
+    /// Number of counters per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+
+    fn get(&self, counter: usize) -> u8 {
+        ((self.table[counter / 16] >> (counter % 16 * 4)) & 0xF) as u8
+    }
+
+    /// Indices of the counters `item` maps to, one per row, derived from a single hash.
+    fn counters(&self, item: &T) -> impl Iterator<Item = usize> + use<T, H> {
+        let hash = self.hasher.hash(&item_bytes(item));
+        let (low, high) = (hash, (hash >> 32) | 1);
+        let width = self.width;
+        (0..DEPTH).map(move |row| {
+            let column = low.wrapping_add(row as u64 * high) as usize & (width - 1);
+            row * width + column
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::XXHasher;
+
+    #[test]
+    fn test_frequency_rises_and_saturates() {
+        let mut sketch = FrequencySketch::<u64, XXHasher>::new(1024);
+        assert_eq!(sketch.frequency(&7), 0);
+        for expected in 1..=20u8 {
+            sketch.increment(&7);
+            assert_eq!(sketch.frequency(&7), expected.min(MAX_COUNT));
+        }
+        assert_eq!(sketch.frequency(&8), 0);
+    }
+
+    #[test]
+    fn test_halves_after_sample_size() {
+        let mut sketch = FrequencySketch::<u64, XXHasher>::with_sample_size(1024, 100);
+        for _ in 0..10 {
+            sketch.increment(&7);
+        }
+        for (item, expected) in [(8, 3), (9, 1)] {
+            for _ in 0..expected {
+                sketch.increment(&item);
+            }
+        }
+        assert_eq!(sketch.frequency(&7), 10);
+
+        // The 100th increment triggers the reset
+        for item in 100..186u64 {
+            sketch.increment(&item);
+        }
+        assert_eq!(sketch.frequency(&7), 5);
+        assert_eq!(sketch.frequency(&8), 1);
+        assert_eq!(sketch.frequency(&9), 0);
+    }
+}
@@ -19,44 +19,34 @@ impl AHasher {
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-impl Hasher64 for AHasher {
-    fn with_seed(seed: u64) -> Self
-    where
-        Self: Sized,
-    {
+    pub fn with_seed(seed: u64) -> Self {
         Self {
             state: RandomState::with_seeds(seed, seed, seed, seed),
         }
     }
 
-    fn hash(&self, bytes: &[u8]) -> u64 {
+    pub fn hash(&self, bytes: &[u8]) -> u64 {
         let mut hasher = self.state.build_hasher();
         hasher.write(bytes);
         hasher.finish()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::hashing::traits::base_tests;
-    use quickcheck::TestResult;
-    use quickcheck_macros::quickcheck;
+    pub fn hash_with_seed(bytes: &[u8], seed: u64) -> u64 {
+        Self::with_seed(seed).hash(bytes)
+    }
+}
 
-    #[quickcheck]
-    fn prop_ahash_deterministic(seed: u64, data: Vec<u8>) -> bool {
-        base_tests::prop_deterministic(seed, data, AHasher::with_seed)
+impl Hasher64 for AHasher {
+    fn with_seed(seed: u64) -> Self {
+        AHasher::with_seed(seed)
     }
 
-    #[quickcheck]
-    fn prop_ahash_different_seeds(seed1: u64, seed2: u64, data: Vec<u8>) -> TestResult {
-        base_tests::prop_different_seeds(seed1, seed2, data, AHasher::with_seed)
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        AHasher::hash(self, bytes)
     }
 
-    #[quickcheck]
-    fn prop_ahash_seed_parameter_varies(param1: u64, param2: u64, data: Vec<u8>) -> TestResult {
-        base_tests::prop_seed_parameter_varies::<AHasher>(param1, param2, data)
+    fn hasher_id(&self) -> &'static str {
+        "ahash"
     }
 }
@@ -0,0 +1,93 @@
+use super::Hasher64;
+use std::cell::Cell;
+
+thread_local! {
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A [`Hasher64`] wrapper that counts how many hashes are computed.
+///
+/// Structures construct hashers internally through `with_seed`, so the count is kept in a
+/// thread-local shared by every `Counting` hasher on the current thread rather than in the
+/// instance. Call [`reset`](Counting::reset) before the operation being measured.
+#[derive(Clone, Default)]
+pub struct Counting<H> {
+    inner: H,
+}
+
+impl<H: Hasher64> Counting<H> {
+    pub fn new(inner: H) -> Self {
+        Self { inner }
+    }
+
+    /// Number of hashes computed on this thread since the last [`reset`](Counting::reset).
+    pub fn calls() -> usize {
+        CALLS.with(Cell::get)
+    }
+
+    pub fn reset() {
+        CALLS.with(|calls| calls.set(0));
+    }
+}
+
+impl<H: Hasher64> Hasher64 for Counting<H> {
+    fn with_seed(seed: u64) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            inner: H::with_seed(seed),
+        }
+    }
+
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        self.inner.hash(bytes)
+    }
+
+    fn hasher_id(&self) -> &'static str {
+        self.inner.hasher_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::bloom::BloomFilter;
+    use crate::filters::traits::ApproximateMembershipQuery;
+    use crate::hashing::XXHasher;
+
+    type CountingXX = Counting<XXHasher>;
+
+    #[test]
+    fn test_counts_hash_calls() {
+        let hasher = CountingXX::with_seed(7);
+        CountingXX::reset();
+        hasher.hash(b"a");
+        hasher.hash(b"b");
+        CountingXX::hash_with_seed(b"c", 1);
+        assert_eq!(CountingXX::calls(), 3);
+        assert_eq!(hasher.hash(b"a"), XXHasher::with_seed(7).hash(b"a"));
+    }
+
+    #[test]
+    fn test_bloom_hashes_twice_per_operation() {
+        // k ranges from 1 to 23 across these rates
+        for fpr in [0.5, 0.1, 0.01, 0.0001, 1e-7] {
+            let mut bf = BloomFilter::<u64, CountingXX>::new(1000, fpr);
+
+            CountingXX::reset();
+            bf.insert(&42);
+            assert_eq!(CountingXX::calls(), 2, "insert with k={}", bf.num_hashes());
+
+            CountingXX::reset();
+            bf.contains(&42);
+            assert_eq!(
+                CountingXX::calls(),
+                2,
+                "contains with k={}",
+                bf.num_hashes()
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::{AHasher, Hasher64, Murmur3Hasher, StdHasher, XXHasher};
+
+/// An object-safe [`Hasher64`], for choosing the hash algorithm at runtime.
+///
+/// Every `Hasher64 + Clone` implements it, and `Box<dyn DynHasher64>` is itself a `Hasher64`,
+/// so a filter can be written as `BloomFilter<T, Box<dyn DynHasher64>>` and configured from,
+/// say, a command-line flag. Because the algorithm is no longer part of the type, operations
+/// combining two such filters compare [`hasher_id`](Hasher64::hasher_id)s at runtime.
+///
+/// Implementors are `Send + Sync`, so such filters can still be shared between threads.
+pub trait DynHasher64: Hasher64 + Send + Sync {
+    /// Boxes a hasher of the same kind seeded with `seed`.
+    fn boxed_with_seed(&self, seed: u64) -> Box<dyn DynHasher64>;
+
+    fn clone_box(&self) -> Box<dyn DynHasher64>;
+}
+
+impl<H: Hasher64 + Clone + Send + Sync + 'static> DynHasher64 for H {
+    fn boxed_with_seed(&self, seed: u64) -> Box<dyn DynHasher64> {
+        Box::new(H::with_seed(seed))
+    }
+
+    fn clone_box(&self) -> Box<dyn DynHasher64> {
+        Box::new(self.clone())
+    }
+}
+
+/// Returns the built-in hasher whose [`hasher_id`](Hasher64::hasher_id) is `name`, seeded with
+/// `seed`, or `None` for an unknown name.
+///
+/// The recognized names are `"ahash"`, `"xxhash3"`, `"murmur3"` and `"std"`.
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn DynHasher64>> {
+    match name {
+        "ahash" => Some(Box::new(AHasher::with_seed(seed))),
+        "xxhash3" => Some(Box::new(XXHasher::with_seed(seed))),
+        "murmur3" => Some(Box::new(Murmur3Hasher::with_seed(seed))),
+        "std" => Some(Box::new(StdHasher::with_seed(seed))),
+        _ => None,
+    }
+}
+
+/// Build dynamically-hashed filters with `with_hasher_instance`, which keeps the instance's
+/// algorithm (as does `rehash_with_seed`). `with_seed` has no instance to take the algorithm
+/// from and falls back to [`XXHasher`].
+impl Hasher64 for Box<dyn DynHasher64> {
+    fn with_seed(seed: u64) -> Self {
+        Box::new(XXHasher::with_seed(seed))
+    }
+
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        (**self).hash(bytes)
+    }
+
+    fn reseeded(&self, seed: u64) -> Self {
+        (**self).boxed_with_seed(seed)
+    }
+
+    fn hasher_id(&self) -> &'static str {
+        (**self).hasher_id()
+    }
+}
+
+impl Clone for Box<dyn DynHasher64> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxed_hasher_delegates() {
+        let boxed: Box<dyn DynHasher64> = Box::new(AHasher::with_seed(3));
+        assert_eq!(boxed.hash(b"key"), AHasher::with_seed(3).hash(b"key"));
+        assert_eq!(boxed.hasher_id(), "ahash");
+
+        let reseeded = boxed.reseeded(4);
+        assert_eq!(reseeded.hasher_id(), "ahash");
+        assert_eq!(reseeded.hash(b"key"), AHasher::with_seed(4).hash(b"key"));
+        assert_eq!(boxed.clone().hash(b"key"), boxed.hash(b"key"));
+    }
+
+    #[test]
+    fn test_by_name() {
+        for name in ["ahash", "xxhash3", "murmur3", "std"] {
+            let hasher = by_name(name, 7).unwrap();
+            assert_eq!(hasher.hasher_id(), name);
+            assert_eq!(hasher.hash(b"key"), by_name(name, 7).unwrap().hash(b"key"));
+            assert_ne!(hasher.hash(b"key"), by_name(name, 8).unwrap().hash(b"key"));
+        }
+        assert!(by_name("md5", 7).is_none());
+        assert!(by_name("AHASH", 7).is_none());
+    }
+}
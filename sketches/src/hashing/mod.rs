@@ -1,9 +1,37 @@
 mod ahash_impl;
+mod combined;
+mod counting;
+mod dynamic;
 mod murmur3_impl;
+mod precomputed;
+mod std_impl;
 mod traits;
 mod xxhash_impl;
 
 pub use ahash_impl::AHasher;
-pub use murmur3_impl::Murmur3Hasher;
-pub use traits::Hasher64;
+pub use combined::Combined;
+pub use counting::Counting;
+pub use dynamic::{DynHasher64, by_name};
+pub use murmur3_impl::{Murmur3Hasher, Murmur3Hasher128};
+pub use precomputed::{PrecomputedHashes, hash_item};
+pub(crate) use precomputed::{item_bytes, row_seeds};
+pub use std_impl::StdHasher;
+pub use traits::{Hasher, Hasher64};
 pub use xxhash_impl::XXHasher;
+
+/// Every hasher in this module must satisfy the [`Hasher64`] contract; add new hashers here,
+/// behind their feature if they have one.
+#[cfg(test)]
+mod contract_tests {
+    use super::{AHasher, Combined, Counting, Hasher64, Murmur3Hasher, StdHasher, XXHasher};
+    use crate::hashing::traits::base_tests::hasher_contract_tests;
+
+    hasher_contract_tests! {
+        ahash: AHasher => AHasher::with_seed,
+        xxhash3: XXHasher => XXHasher::with_seed,
+        murmur3: Murmur3Hasher => Murmur3Hasher::with_seed,
+        std: StdHasher => StdHasher::with_seed,
+        counting: Counting<XXHasher> => Counting::<XXHasher>::with_seed,
+        combined: Combined<XXHasher, Murmur3Hasher> => Combined::<XXHasher, Murmur3Hasher>::with_seed,
+    }
+}
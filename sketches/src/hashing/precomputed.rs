@@ -0,0 +1,75 @@
+use super::Hasher64;
+use std::hash::Hash;
+
+/// The two base hashes of an item, computed once and shared across several sketches.
+///
+/// Sketches keyed on the same item with the same hasher type and seeds accept these through
+/// their `*_precomputed` methods instead of hashing the item again, so a pipeline feeding
+/// several structures pays for hashing once per item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecomputedHashes {
+    pub first: u64,
+    pub second: u64,
+}
+
+/// Hashes `item` with `H` seeded with each of `seeds`.
+///
+/// The result matches a seeded structure's own hashing when `seeds` are its base seeds, e.g.
+/// [`BloomFilter::base_seeds`](crate::filters::bloom::BloomFilter::base_seeds).
+pub fn hash_item<H: Hasher64, T: Hash>(item: &T, seeds: [u64; 2]) -> PrecomputedHashes {
+    let bytes = item_bytes(item);
+    PrecomputedHashes {
+        first: H::with_seed(seeds[0]).hash(&bytes),
+        second: H::with_seed(seeds[1]).hash(&bytes),
+    }
+}
+
+/// Seeds for the `count` independent hashers of a multi-row sketch.
+///
+/// Consecutive seeds such as `0..count` differ in a bit or two, which hashers with weak seed
+/// mixing turn into correlated rows. These are the outputs of a SplitMix64 sequence instead,
+/// so every bit differs at random between rows, including the low 32 that
+/// [`Murmur3Hasher`](super::Murmur3Hasher) keeps.
+pub(crate) fn row_seeds(count: usize) -> impl Iterator<Item = u64> {
+    (1..=count as u64).map(|row| {
+        let mut z = row.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+/// The bytes sketches feed to their hashers for an item: its `std::hash::Hash` digest.
+///
+/// `DefaultHasher`'s algorithm may change between Rust releases, so these bytes, and every
+/// position derived from them, are only stable within one build. Sketches meant to be
+/// persisted should be keyed by bytes instead.
+pub(crate) fn item_bytes<T: Hash>(item: &T) -> [u8; 8] {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher as StdHasher;
+    let mut hasher = DefaultHasher::new();
+    item.hash(&mut hasher);
+    hasher.finish().to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_tuple_digests_do_not_collide() {
+        // Tuples are folded into an 8-byte digest before hashing; check that no two of
+        // 300k structured, near-identical tuples share one
+        let digests: HashSet<[u8; 8]> = (0..1000u64)
+            .flat_map(|a| (0..300u32).map(move |b| item_bytes(&(a, b))))
+            .collect();
+        assert_eq!(digests.len(), 300_000);
+    }
+
+    #[test]
+    fn test_row_seeds_differ_in_their_low_bits() {
+        let low: HashSet<u32> = row_seeds(10_000).map(|seed| seed as u32).collect();
+        assert_eq!(low.len(), 10_000);
+    }
+}
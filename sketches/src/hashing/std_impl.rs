@@ -0,0 +1,52 @@
+use super::Hasher64;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// The standard library's [`DefaultHasher`] (currently SipHash-1-3), as a baseline for the
+/// faster non-cryptographic hashers.
+///
+/// `DefaultHasher` takes no key, so the seed is written ahead of the input. Its algorithm is
+/// unspecified and may change between Rust releases, so unlike the other hashers, even
+/// byte-keyed filters built with it should not be persisted and reloaded by a different
+/// toolchain. (Items of type `T` are digested with `DefaultHasher` before reaching any
+/// hasher, so `T`-keyed filters share the caveat whatever they hash with; see
+/// [`BloomFilter::as_bytes`](crate::filters::bloom::BloomFilter::as_bytes).)
+#[derive(Clone, Default)]
+pub struct StdHasher {
+    seed: u64,
+}
+
+impl StdHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn hash(&self, bytes: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u64(self.seed);
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    pub fn hash_with_seed(bytes: &[u8], seed: u64) -> u64 {
+        Self::with_seed(seed).hash(bytes)
+    }
+}
+
+impl Hasher64 for StdHasher {
+    fn with_seed(seed: u64) -> Self {
+        StdHasher::with_seed(seed)
+    }
+
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        StdHasher::hash(self, bytes)
+    }
+
+    fn hasher_id(&self) -> &'static str {
+        "std"
+    }
+}
@@ -1,3 +1,33 @@
+/// A seedable hash function over byte slices.
+///
+/// `Output` is the natural width of the hash: 64-bit hashers implement [`Hasher64`] and get this
+/// trait for free with `Output = u64`, while wider hashers (e.g. [`Murmur3Hasher128`]) implement
+/// it directly so structures needing two independent lanes can split one evaluation instead of
+/// hashing twice.
+///
+/// [`Murmur3Hasher128`]: crate::hashing::Murmur3Hasher128
+pub trait Hasher {
+    type Output;
+
+    fn with_seed(seed: u64) -> Self
+    where
+        Self: Sized;
+
+    fn hash(&self, bytes: &[u8]) -> Self::Output;
+
+    fn hash_with_seed(bytes: &[u8], seed: u64) -> Self::Output
+    where
+        Self: Sized,
+    {
+        Self::with_seed(seed).hash(bytes)
+    }
+}
+
+/// A 64-bit hash function, the one the filters are generic over.
+///
+/// Every implementation is also a [`Hasher`] with `Output = u64` through a blanket impl. The
+/// built-in hashers also have `with_seed`, `hash` and `hash_with_seed` as inherent methods, so
+/// calls on a concrete type stay unambiguous with both traits in scope.
 pub trait Hasher64 {
     fn with_seed(seed: u64) -> Self
     where
@@ -11,6 +41,35 @@ pub trait Hasher64 {
     {
         Self::with_seed(seed).hash(bytes)
     }
+
+    /// A hasher of the same kind as `self`, seeded with `seed`.
+    ///
+    /// Equivalent to `with_seed` for static hashers; type-erased hashers override it to keep
+    /// the underlying algorithm.
+    fn reseeded(&self, seed: u64) -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_seed(seed)
+    }
+
+    /// Identifies the hash algorithm, so filters using type-erased hashers can refuse to be
+    /// combined with filters hashing differently.
+    fn hasher_id(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<H: Hasher64> Hasher for H {
+    type Output = u64;
+
+    fn with_seed(seed: u64) -> Self {
+        <H as Hasher64>::with_seed(seed)
+    }
+
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        <H as Hasher64>::hash(self, bytes)
+    }
 }
 
 #[cfg(test)]
@@ -62,4 +121,128 @@ pub mod base_tests {
         let hash2 = H::hash_with_seed(&data, seed2);
         TestResult::from_bool(hash1 != hash2)
     }
+
+    /// Generates a module of quickcheck tests running every property above against a hasher.
+    ///
+    /// Takes the module name, the hasher type and its `with_seed` constructor:
+    ///
+    /// ```ignore
+    /// hasher_contract_tests! {
+    ///     xxhash: XXHasher => XXHasher::with_seed,
+    /// }
+    /// ```
+    macro_rules! hasher_contract_tests {
+        ($($name:ident: $hasher:ty => $constructor:expr),* $(,)?) => {
+            $(
+                mod $name {
+                    use super::*;
+                    use crate::hashing::traits::base_tests;
+                    use quickcheck::TestResult;
+                    use quickcheck_macros::quickcheck;
+
+                    #[quickcheck]
+                    fn prop_deterministic(seed: u64, data: Vec<u8>) -> bool {
+                        base_tests::prop_deterministic::<$hasher, _>(seed, data, $constructor)
+                    }
+
+                    #[quickcheck]
+                    fn prop_different_seeds(seed1: u64, seed2: u64, data: Vec<u8>) -> TestResult {
+                        base_tests::prop_different_seeds::<$hasher, _>(
+                            seed1,
+                            seed2,
+                            data,
+                            $constructor,
+                        )
+                    }
+
+                    #[quickcheck]
+                    fn prop_seed_parameter_varies(
+                        seed1: u64,
+                        seed2: u64,
+                        data: Vec<u8>,
+                    ) -> TestResult {
+                        base_tests::prop_seed_parameter_varies::<$hasher>(seed1, seed2, data)
+                    }
+                }
+            )*
+        };
+    }
+
+    pub(crate) use hasher_contract_tests;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::{AHasher, Murmur3Hasher, Murmur3Hasher128, XXHasher};
+
+    fn generic_hash<H: Hasher>(seed: u64, bytes: &[u8]) -> H::Output {
+        H::with_seed(seed).hash(bytes)
+    }
+
+    fn agrees_with_hasher64<H: Hasher64>() -> bool {
+        (0..100u64).all(|seed| {
+            let bytes = seed.to_le_bytes();
+            generic_hash::<H>(seed, &bytes) == <H as Hasher64>::hash_with_seed(&bytes, seed)
+        })
+    }
+
+    #[test]
+    fn test_hasher64_impls_are_u64_hashers() {
+        assert!(agrees_with_hasher64::<AHasher>());
+        assert!(agrees_with_hasher64::<XXHasher>());
+        assert!(agrees_with_hasher64::<Murmur3Hasher>());
+    }
+
+    #[test]
+    fn test_concrete_calls_resolve_with_both_traits_in_scope() {
+        assert_eq!(
+            AHasher::with_seed(1).hash(b"key"),
+            AHasher::hash_with_seed(b"key", 1)
+        );
+        assert_eq!(
+            XXHasher::with_seed(1).hash(b"key"),
+            <XXHasher as Hasher>::hash_with_seed(b"key", 1)
+        );
+        assert_eq!(
+            Murmur3Hasher::with_seed(1).hash(b"key"),
+            <Murmur3Hasher as Hasher64>::hash_with_seed(b"key", 1)
+        );
+    }
+
+    #[test]
+    fn test_wide_output() {
+        let wide: u128 = generic_hash::<Murmur3Hasher128>(3, b"sketch");
+        assert_ne!(wide >> 64, 0);
+    }
+}
+
+#[cfg(test)]
+mod hasher64_only_tests {
+    use crate::hashing::{AHasher, Hasher64, Murmur3Hasher, StdHasher, XXHasher};
+
+    fn generic_hash<H: Hasher64>(seed: u64, bytes: &[u8]) -> u64 {
+        H::with_seed(seed).hash(bytes)
+    }
+
+    #[test]
+    fn test_existing_usage_compiles_with_hasher64_alone() {
+        assert_eq!(
+            AHasher::with_seed(7).hash(b"key"),
+            generic_hash::<AHasher>(7, b"key")
+        );
+        assert_eq!(
+            XXHasher::hash_with_seed(b"key", 7),
+            generic_hash::<XXHasher>(7, b"key")
+        );
+        assert_eq!(
+            Murmur3Hasher::with_seed(7).hash(b"key"),
+            generic_hash::<Murmur3Hasher>(7, b"key")
+        );
+        assert_eq!(
+            StdHasher::with_seed(7).hash(b"key"),
+            generic_hash::<StdHasher>(7, b"key")
+        );
+        assert_eq!(AHasher::with_seed(7).hasher_id(), "ahash");
+    }
 }
@@ -14,40 +14,26 @@ impl XXHasher {
     pub fn with_seed(seed: u64) -> Self {
         Self { seed }
     }
-}
 
-impl Hasher64 for XXHasher {
-    fn with_seed(seed: u64) -> Self
-    where
-        Self: Sized,
-    {
-        Self { seed }
+    pub fn hash(&self, bytes: &[u8]) -> u64 {
+        xxh3_64_with_seed(bytes, self.seed)
     }
 
-    fn hash(&self, bytes: &[u8]) -> u64 {
-        xxh3_64_with_seed(bytes, self.seed)
+    pub fn hash_with_seed(bytes: &[u8], seed: u64) -> u64 {
+        xxh3_64_with_seed(bytes, seed)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::hashing::traits::base_tests;
-    use quickcheck::TestResult;
-    use quickcheck_macros::quickcheck;
-
-    #[quickcheck]
-    fn prop_xxhash_deterministic(seed: u64, data: Vec<u8>) -> bool {
-        base_tests::prop_deterministic(seed, data, XXHasher::with_seed)
+impl Hasher64 for XXHasher {
+    fn with_seed(seed: u64) -> Self {
+        XXHasher::with_seed(seed)
     }
 
-    #[quickcheck]
-    fn prop_xxhash_different_seeds(seed1: u64, seed2: u64, data: Vec<u8>) -> TestResult {
-        base_tests::prop_different_seeds(seed1, seed2, data, XXHasher::with_seed)
+    fn hash(&self, bytes: &[u8]) -> u64 {
+        XXHasher::hash(self, bytes)
     }
 
-    #[quickcheck]
-    fn prop_ahash_seed_parameter_varies(param1: u64, param2: u64, data: Vec<u8>) -> TestResult {
-        base_tests::prop_seed_parameter_varies::<XXHasher>(param1, param2, data)
+    fn hasher_id(&self) -> &'static str {
+        "xxhash3"
     }
 }
@@ -1,3 +1,8 @@
 pub mod benchmarks;
+pub mod cardinality;
+pub mod dedup;
 pub mod filters;
+pub mod frequency;
 pub mod hashing;
+pub mod quantile;
+pub mod similarity;
@@ -0,0 +1,205 @@
+/// A fixed-precision histogram for integer values with logarithmic bucket boundaries.
+///
+/// Follows the HdrHistogram layout: values below `2^b` get one bucket each, and every further
+/// power of two is split into `2^(b-1)` linear sub-buckets, where `b` is derived from the number
+/// of significant figures. Any recorded value is therefore reported with a relative error of at
+/// most `10^-significant_figures`.
+///
+/// Compared to t-digest this gives O(1) updates and exact merges at the cost of a fixed
+/// precision, which is a good fit for bounded integer ranges such as microsecond latencies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FixedBucketHistogram {
+    significant_figures: u8,
+    sub_bucket_bits: u32, // log2 of the number of exact buckets
+    counts: Vec<u64>,     // Grown lazily up to the highest recorded bucket
+    total: u64,
+}
+
+impl FixedBucketHistogram {
+    /// Creates an empty histogram.
+    ///
+    /// `significant_figures` must be in `1..=5`. Each extra figure increases the bucket density,
+    /// and therefore memory, roughly tenfold.
+    pub fn new(significant_figures: u8) -> Self {
+        assert!(
+            (1..=5).contains(&significant_figures),
+            "Significant figures must be between 1 and 5"
+        );
+        let exact_values = 2 * 10u64.pow(significant_figures as u32);
+        FixedBucketHistogram {
+            significant_figures,
+            sub_bucket_bits: exact_values.next_power_of_two().trailing_zeros(),
+            counts: Vec::new(),
+            total: 0,
+        }
+    }
+
+    pub fn significant_figures(&self) -> u8 {
+        self.significant_figures
+    }
+
+    /// Records a single occurrence of `value`.
+    pub fn record(&mut self, value: u64) {
+        self.record_n(value, 1);
+    }
+
+    /// Records `count` occurrences of `value`.
+    pub fn record_n(&mut self, value: u64, count: u64) {
+        let index = self.bucket_index(value);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += count;
+        self.total += count;
+    }
+
+    /// Returns the value at quantile `q` (in `[0, 1]`), or `None` if nothing was recorded.
+    pub fn quantile(&self, q: f64) -> Option<u64> {
+        assert!((0.0..=1.0).contains(&q), "Quantile must be in [0, 1]");
+        if self.total == 0 {
+            return None;
+        }
+        let rank = ((q * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(self.representative_value(index));
+            }
+        }
+        unreachable!("rank never exceeds the total count")
+    }
+
+    /// Adds all counts from `other` into `self`. Merging is exact.
+    ///
+    /// # Panics
+    /// If the two histograms were created with different significant figures.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.significant_figures, other.significant_figures,
+            "Cannot merge histograms with different significant figures"
+        );
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, &other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+        self.total += other.total;
+    }
+
+    /// Total number of recorded values.
+    pub fn len(&self) -> u64 {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    fn bucket_index(&self, value: u64) -> usize {
+        let exact = 1u64 << self.sub_bucket_bits;
+        if value < exact {
+            return value as usize;
+        }
+        let half = exact >> 1;
+        let shift = (64 - value.leading_zeros()) - self.sub_bucket_bits;
+        let sub_bucket = value >> shift;
+        (exact + (shift as u64 - 1) * half + (sub_bucket - half)) as usize
+    }
+
+    /// Midpoint of the range of values sharing a bucket.
+    fn representative_value(&self, index: usize) -> u64 {
+        let exact = 1u64 << self.sub_bucket_bits;
+        let index = index as u64;
+        if index < exact {
+            return index;
+        }
+        let half = exact >> 1;
+        let offset = index - exact;
+        let shift = offset / half + 1;
+        let lowest = (offset % half + half) << shift;
+        lowest + ((1u64 << shift) - 1) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_values_are_exact() {
+        let mut hist = FixedBucketHistogram::new(2);
+        for v in 0..100 {
+            hist.record(v);
+        }
+        assert_eq!(hist.quantile(0.0), Some(0));
+        assert_eq!(hist.quantile(0.5), Some(49));
+        assert_eq!(hist.quantile(1.0), Some(99));
+    }
+
+    #[test]
+    fn test_quantile_within_relative_precision() {
+        for significant_figures in 1..=3 {
+            let mut hist = FixedBucketHistogram::new(significant_figures);
+            for v in 1..=1_000_000u64 {
+                hist.record(v);
+            }
+            let precision = 10f64.powi(-(significant_figures as i32));
+            for (q, expected) in [(0.5, 500_000.0), (0.99, 990_000.0), (0.999, 999_000.0)] {
+                let estimate = hist.quantile(q).unwrap() as f64;
+                let error = (estimate - expected).abs() / expected;
+                assert!(
+                    error <= precision,
+                    "sigfigs={} q={} estimate={} error={}",
+                    significant_figures,
+                    q,
+                    estimate,
+                    error
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let mut hist = FixedBucketHistogram::new(3);
+        hist.record(u64::MAX);
+        let estimate = hist.quantile(1.0).unwrap() as f64;
+        assert!((u64::MAX as f64 - estimate) / (u64::MAX as f64) <= 1e-3);
+    }
+
+    #[test]
+    fn test_merge_is_exact() {
+        let mut a = FixedBucketHistogram::new(2);
+        let mut b = FixedBucketHistogram::new(2);
+        let mut all = FixedBucketHistogram::new(2);
+        for v in 0..10_000u64 {
+            let value = v * v % 77_777;
+            if v % 3 == 0 {
+                a.record(value);
+            } else {
+                b.record(value);
+            }
+            all.record(value);
+        }
+
+        a.merge(&b);
+        assert_eq!(a, all);
+        assert_eq!(a.len(), 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "different significant figures")]
+    fn test_merge_rejects_mismatched_precision() {
+        let mut a = FixedBucketHistogram::new(2);
+        a.merge(&FixedBucketHistogram::new(3));
+    }
+
+    #[test]
+    fn test_empty_histogram() {
+        let hist = FixedBucketHistogram::new(2);
+        assert!(hist.is_empty());
+        assert_eq!(hist.quantile(0.5), None);
+    }
+}
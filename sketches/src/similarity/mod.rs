@@ -0,0 +1,13 @@
+use crate::filters::error::MergeError;
+
+/// A sketch that estimates how similar the sets summarized by two sketches are.
+///
+/// Similarities are in `[0, 1]`: `1.0` for identical sets and `0.0` for disjoint ones. What
+/// the number measures depends on the sketch and is documented on each implementation; the
+/// common interface lets near-duplicate detection swap one estimator for another.
+///
+/// Only sketches built with the same parameters and hashers can be compared; anything else
+/// is a [`MergeError`].
+pub trait SimilarityEstimator {
+    fn similarity(&self, other: &Self) -> Result<f64, MergeError>;
+}
@@ -0,0 +1,133 @@
+/// Generic correctness properties for any `ApproximateMembershipQuery` implementation
+///
+/// Each filter gets the full suite by adding one `amq_properties!` line with a constructor
+/// taking the capacity to size the filter for.
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use sketches::filters::bloom::{AdaptiveBloomFilter, BloomFilter, DenseBits};
+use sketches::filters::counting::CountingBloomFilter;
+use sketches::filters::traits::ApproximateMembershipQuery;
+use sketches::hashing::{AHasher, Combined, Murmur3Hasher, XXHasher};
+
+/// Property: All inserted items must be found on lookup
+fn check_no_false_negatives<F, C>(constructor: C, items: &[u64]) -> Result<(), TestCaseError>
+where
+    F: ApproximateMembershipQuery<u64>,
+    C: Fn(usize) -> F,
+{
+    let mut filter = constructor(items.len() + 100);
+    for item in items {
+        filter.insert(item);
+    }
+    for item in items {
+        prop_assert!(filter.contains(item), "False negative for item: {}", item);
+    }
+    Ok(())
+}
+
+/// Property: Filter length increases monotonically with every insert
+fn check_length_increases<F, C>(constructor: C, items: &[u64]) -> Result<(), TestCaseError>
+where
+    F: ApproximateMembershipQuery<u64>,
+    C: Fn(usize) -> F,
+{
+    let mut filter = constructor(items.len() + 10);
+    let mut prev_len = 0;
+    for item in items {
+        filter.insert(item);
+        let new_len = filter.len();
+        prop_assert!(new_len > prev_len, "Length did not increase after insert");
+        prev_len = new_len;
+    }
+    prop_assert_eq!(filter.len(), items.len());
+    Ok(())
+}
+
+/// Property: Inserting duplicate items still works (idempotent for queries)
+fn check_duplicate_inserts<F, C>(constructor: C, items: &[u64]) -> Result<(), TestCaseError>
+where
+    F: ApproximateMembershipQuery<u64>,
+    C: Fn(usize) -> F,
+{
+    let mut filter = constructor(items.len() * 3);
+    for item in items {
+        filter.insert(item);
+        filter.insert(item); // duplicate
+        filter.insert(item); // triplicate
+    }
+    for item in items {
+        prop_assert!(filter.contains(item));
+    }
+    Ok(())
+}
+
+/// Property: Clearing empties the filter and forgets every item
+fn check_clear_empties<F, C>(constructor: C, items: &[u64]) -> Result<(), TestCaseError>
+where
+    F: ApproximateMembershipQuery<u64>,
+    C: Fn(usize) -> F,
+{
+    let mut filter = constructor(items.len() + 10);
+    for item in items {
+        filter.insert(item);
+    }
+    filter.clear();
+    prop_assert!(filter.is_empty());
+    prop_assert_eq!(filter.len(), 0);
+    for item in items {
+        prop_assert!(!filter.contains(item), "Item {} survived clear", item);
+    }
+
+    // The filter stays usable
+    filter.insert(&items[0]);
+    prop_assert!(filter.contains(&items[0]));
+    Ok(())
+}
+
+macro_rules! amq_properties {
+    ($name:ident, $constructor:expr) => {
+        mod $name {
+            use super::*;
+
+            proptest! {
+                #[test]
+                fn no_false_negatives(items in prop::collection::vec(any::<u64>(), 0..1000)) {
+                    check_no_false_negatives($constructor, &items)?;
+                }
+
+                #[test]
+                fn length_increases(items in prop::collection::vec(any::<u64>(), 1..100)) {
+                    check_length_increases($constructor, &items)?;
+                }
+
+                #[test]
+                fn duplicate_inserts(items in prop::collection::vec(any::<u64>(), 1..50)) {
+                    check_duplicate_inserts($constructor, &items)?;
+                }
+
+                #[test]
+                fn clear_empties(items in prop::collection::vec(any::<u64>(), 1..100)) {
+                    check_clear_empties($constructor, &items)?;
+                }
+            }
+        }
+    };
+}
+
+amq_properties!(bloom_ahash, |n| BloomFilter::<u64, AHasher>::new(n, 0.01));
+amq_properties!(bloom_xxhash, |n| BloomFilter::<u64, XXHasher>::new(n, 0.01));
+amq_properties!(bloom_murmur3, |n| BloomFilter::<u64, Murmur3Hasher>::new(
+    n, 0.01
+));
+amq_properties!(bloom_combined, |n| {
+    BloomFilter::<u64, Combined<XXHasher, Murmur3Hasher>>::new(n, 0.01)
+});
+amq_properties!(bloom_dense_bits, |n| {
+    BloomFilter::<u64, AHasher, DenseBits>::new(n, 0.01)
+});
+amq_properties!(adaptive_bloom, |n| {
+    AdaptiveBloomFilter::<u64, AHasher>::new(n, 0.01)
+});
+amq_properties!(counting_bloom, |n| {
+    CountingBloomFilter::<u64, AHasher>::new(n, 0.01)
+});
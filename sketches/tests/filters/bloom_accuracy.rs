@@ -1,3 +1,4 @@
+use sketches::benchmarks::generate_absent;
 use sketches::filters::bloom::BloomFilter;
 use sketches::filters::traits::ApproximateMembershipQuery;
 use sketches::hashing::AHasher;
@@ -9,18 +10,17 @@ fn test_bloom_fpr_within_bounds() {
     let mut filter = BloomFilter::<_, AHasher>::new(n, f);
 
     // Insert n items
-    for i in 0..n as u64 {
-        filter.insert(&i);
+    let inserted: Vec<u64> = (0..n as u64).collect();
+    for i in &inserted {
+        filter.insert(i);
     }
 
     // Query 100k items not in filter
     let m = 100_000;
-    let mut false_positives = 0;
-    for i in n as u64..(n as u64 + m) {
-        if filter.contains(&i) {
-            false_positives += 1;
-        }
-    }
+    let false_positives = generate_absent(&inserted, m, 42)
+        .iter()
+        .filter(|i| filter.contains(i))
+        .count();
 
     let empirical_fpr = false_positives as f64 / m as f64;
     println!(
@@ -3,6 +3,7 @@ use rand::RngCore;
 ///
 /// These tests cover boundary conditions, extreme inputs, and unusual scenarios
 use sketches::filters::bloom::BloomFilter;
+use sketches::filters::error::SizingError;
 use sketches::filters::traits::ApproximateMembershipQuery;
 use sketches::hashing::AHasher;
 
@@ -54,6 +55,55 @@ fn test_large_capacity() {
     assert_eq!(filter.len(), 0);
 }
 
+#[test]
+fn test_overflowing_capacity_is_an_error() {
+    // Needs ~1.4e22 bits, which doesn't fit a usize
+    assert_eq!(
+        BloomFilter::<u64, AHasher>::try_new(usize::MAX, 1e-3).err(),
+        Some(SizingError::TooManyBits)
+    );
+    assert_eq!(
+        BloomFilter::<u64, AHasher>::try_new(usize::MAX / 2, 0.0).err(),
+        Some(SizingError::InvalidParameters)
+    );
+    let filter = BloomFilter::<u64, AHasher>::try_new(10_000_000, 0.01).unwrap();
+    assert_eq!(filter.capacity(), 10_000_000);
+}
+
+#[test]
+fn test_rates_of_one_or_more_are_an_error() {
+    for rate in [1.0, 1.5] {
+        assert_eq!(
+            BloomFilter::<u64, AHasher>::try_new(1000, rate).err(),
+            Some(SizingError::InvalidParameters),
+            "rate {}",
+            rate
+        );
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "Cannot size filter: capacity must be positive and the rate within (0, 1)"
+)]
+fn test_rate_of_one_panics_in_new() {
+    let _ = BloomFilter::<u64, AHasher>::new(1000, 1.0);
+}
+
+#[test]
+#[should_panic(
+    expected = "Cannot size filter: capacity must be positive and the rate within (0, 1)"
+)]
+fn test_rate_above_one_panics_in_new() {
+    let _ = BloomFilter::<u64, AHasher>::with_seed_offsets(1000, 1.5, [1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "Cannot size filter")]
+fn test_overflowing_capacity_panics_in_new() {
+    let _ = BloomFilter::<u64, AHasher>::new(usize::MAX, 1e-3);
+}
+
 #[test]
 fn test_single_item() {
     // Test with just one item
@@ -156,3 +206,30 @@ fn test_capacity_vs_actual_insertions() {
     // But FPR might be higher than configured for non-inserted items
     // This is expected behavior
 }
+
+#[test]
+fn test_tuple_keys() {
+    // Composite keys hash through `Hash` like any other item
+    let mut filter = BloomFilter::<(u64, u32), AHasher>::new(50_000, 0.01);
+    for user in 0..500u64 {
+        for shard in 0..100u32 {
+            filter.insert(&(user, shard));
+        }
+    }
+
+    for user in 0..500u64 {
+        for shard in 0..100u32 {
+            assert!(filter.contains(&(user, shard)));
+        }
+    }
+
+    // Swapped and shifted components are different keys, so the FPR must stay near target
+    let absent = (0..500u64)
+        .flat_map(|user| (100..200u32).map(move |shard| (user, shard)))
+        .chain((0..100u64).flat_map(|shard| (500..1000u32).map(move |user| (shard << 32, user))));
+    let (queries, false_positives) = absent.fold((0, 0), |(queries, fps), key| {
+        (queries + 1, fps + filter.contains(&key) as usize)
+    });
+    let fpr = false_positives as f64 / queries as f64;
+    assert!(fpr < 0.015, "Tuple keys collide: FPR {:.4}", fpr);
+}
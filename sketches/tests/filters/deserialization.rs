@@ -0,0 +1,113 @@
+/// Robustness of the filter deserializers against malformed input
+///
+/// Decoding must reject bad bytes with an error, never panic, and never allocate more than
+/// the input's own size suggests.
+use proptest::prelude::*;
+use sketches::filters::bloom::{BloomFilter, HEADER_LEN};
+use sketches::filters::error::DecodeError;
+use sketches::filters::traits::ApproximateMembershipQuery;
+use sketches::hashing::AHasher;
+
+type Filter = BloomFilter<u64, AHasher>;
+
+fn valid_blob() -> Vec<u8> {
+    let mut filter = Filter::new(100, 0.01);
+    for i in 0..50u64 {
+        filter.insert(&i);
+    }
+    filter.as_bytes()
+}
+
+/// Overwrites header field `index` (0 = m, 1 = k, 2 = n, 3 = f, 4 = count, 5 = seed, 6 and 7 =
+/// seed offsets). The eight fields end the header.
+fn with_field(mut bytes: Vec<u8>, index: usize, value: [u8; 8]) -> Vec<u8> {
+    let start = HEADER_LEN - 8 * 8 + index * 8;
+    bytes[start..start + 8].copy_from_slice(&value);
+    bytes
+}
+
+#[test]
+fn test_valid_blob_decodes() {
+    let decoded = Filter::from_bytes(&valid_blob()).unwrap();
+    assert!((0..50u64).all(|i| decoded.contains(&i)));
+}
+
+#[test]
+fn test_truncated_blobs() {
+    let blob = valid_blob();
+    assert_eq!(Filter::from_bytes(&[]).err(), Some(DecodeError::BadHeader));
+    assert_eq!(
+        Filter::from_bytes(&blob[..HEADER_LEN - 1]).err(),
+        Some(DecodeError::BadHeader)
+    );
+    assert_eq!(
+        Filter::from_bytes(&blob[..blob.len() - 1]).err(),
+        Some(DecodeError::LengthMismatch)
+    );
+}
+
+#[test]
+fn test_bad_magic() {
+    let mut blob = valid_blob();
+    blob[0] = b'X';
+    assert_eq!(
+        Filter::from_bytes(&blob).err(),
+        Some(DecodeError::BadHeader)
+    );
+}
+
+#[test]
+fn test_oversized_m_is_rejected_without_allocating() {
+    // Would need 2 EiB of bits if trusted
+    let blob = with_field(valid_blob(), 0, (u64::MAX / 8).to_le_bytes());
+    assert_eq!(
+        Filter::from_bytes(&blob).err(),
+        Some(DecodeError::LengthMismatch)
+    );
+}
+
+#[test]
+fn test_bad_parameters() {
+    for (index, value) in [
+        (0, 0u64.to_le_bytes()),
+        (1, 0u64.to_le_bytes()),
+        (1, u64::MAX.to_le_bytes()),
+        (2, 0u64.to_le_bytes()),
+        (3, 0f64.to_le_bytes()),
+        (3, 1.5f64.to_le_bytes()),
+        (3, f64::NAN.to_le_bytes()),
+        (3, (-0.01f64).to_le_bytes()),
+    ] {
+        let blob = with_field(valid_blob(), index, value);
+        assert_eq!(
+            Filter::from_bytes(&blob).err(),
+            Some(DecodeError::InvalidParameters),
+            "field {} = {:?}",
+            index,
+            value
+        );
+    }
+}
+
+proptest! {
+    /// Property: Arbitrary bytes never panic the decoder
+    #[test]
+    fn arbitrary_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        let _ = Filter::from_bytes(&bytes);
+    }
+
+    /// Property: A valid blob with corrupted bytes either decodes or errors, never panics
+    #[test]
+    fn corrupted_blob_never_panics(
+        corruptions in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8)
+    ) {
+        let mut blob = valid_blob();
+        for (index, byte) in corruptions {
+            let i = index.index(blob.len());
+            blob[i] = byte;
+        }
+        if let Ok(filter) = Filter::from_bytes(&blob) {
+            let _ = filter.contains(&1);
+        }
+    }
+}
@@ -4,27 +4,6 @@ use sketches::filters::traits::ApproximateMembershipQuery;
 use sketches::hashing::AHasher;
 
 proptest! {
-    /// Property: Standard Bloom filter has no false negatives
-    /// For any set of items inserted, all must be found on lookup
-    #[test]
-    fn bloom_no_false_negatives(
-        items in prop::collection::vec(any::<u64>(), 0..1000)
-    ) {
-        let mut filter = BloomFilter::<_, AHasher>::new(
-            items.len() + 100,
-            0.01,
-        );
-
-        for item in &items {
-            filter.insert(item);
-        }
-
-        for item in &items {
-            prop_assert!(filter.contains(item), "False negative for item: {}", item);
-        }
-    }
-
-
     /// Property: Empty filter should not contain any items
     #[test]
     fn bloom_empty_contains_nothing(
@@ -47,44 +26,6 @@ proptest! {
         prop_assert!(fpr < 0.1, "Too many false positives in empty filter: {}", fpr);
     }
 
-    /// Property: Filter length increases monotonically
-    #[test]
-    fn bloom_length_increases(
-        items in prop::collection::vec(any::<u64>(), 1..100)
-    ) {
-        let mut filter = BloomFilter::<_, AHasher>::new(items.len() + 10, 0.01);
-        let mut prev_len = 0;
-
-        for item in &items {
-            filter.insert(item);
-            let new_len = filter.len();
-            prop_assert!(new_len > prev_len, "Length did not increase after insert");
-            prev_len = new_len;
-        }
-
-        prop_assert_eq!(filter.len(), items.len());
-    }
-
-    /// Property: Inserting duplicate items still works (idempotent for queries)
-    #[test]
-    fn bloom_duplicate_inserts(
-        items in prop::collection::vec(any::<u64>(), 1..50)
-    ) {
-        let mut filter = BloomFilter::<_, AHasher>::new(items.len() * 3, 0.01);
-
-        // Insert each item multiple times
-        for item in &items {
-            filter.insert(item);
-            filter.insert(item); // duplicate
-            filter.insert(item); // triplicate
-        }
-
-        // All items should still be found
-        for item in &items {
-            prop_assert!(filter.contains(item));
-        }
-    }
-
     /// Property: Different items with same hash behavior
     #[test]
     fn bloom_handles_hash_collisions(
@@ -1,3 +1,6 @@
+mod amq_properties;
 mod bloom_accuracy;
 mod bloom_edge_cases;
+mod deserialization;
 mod filter_properties;
+mod serialization_round_trip;
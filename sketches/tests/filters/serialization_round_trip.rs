@@ -0,0 +1,69 @@
+/// Round-trip fidelity of filter serialization
+///
+/// A decoded filter must answer every query exactly as the original did, not just at the same
+/// rate, so any bit-ordering or endianness slip in a serialization path shows up here.
+use proptest::prelude::*;
+use sketches::filters::bloom::{BloomFilter, ByteBloomFilter, DenseBits};
+use sketches::filters::traits::ApproximateMembershipQuery;
+use sketches::hashing::{AHasher, XXHasher};
+
+proptest! {
+    /// Property: Decoding a serialized filter preserves membership for present and absent items
+    #[test]
+    fn round_trip_preserves_membership(
+        items in prop::collection::vec(any::<u64>(), 1..500),
+        absent in prop::collection::vec(any::<u64>(), 200),
+        fpr in 0.0001f64..0.3,
+        seed in any::<u64>(),
+    ) {
+        let mut filter = BloomFilter::<u64, AHasher>::new(items.len(), fpr);
+        filter.rehash_with_seed(seed, items.iter().copied());
+
+        let decoded = BloomFilter::<u64, AHasher>::from_bytes(&filter.as_bytes()).unwrap();
+
+        prop_assert_eq!(decoded.len(), filter.len());
+        prop_assert_eq!(decoded.base_seeds(), filter.base_seeds());
+        for item in items.iter().chain(&absent) {
+            prop_assert_eq!(decoded.contains(item), filter.contains(item), "item {}", item);
+        }
+        prop_assert_eq!(decoded.as_bytes(), filter.as_bytes());
+    }
+
+    /// Property: The same holds across storage backends and for custom seed offsets
+    #[test]
+    fn round_trip_across_storage(
+        items in prop::collection::vec(any::<u64>(), 1..300),
+        absent in prop::collection::vec(any::<u64>(), 200),
+        offsets in any::<[u64; 2]>().prop_filter("offsets must differ", |o| o[0] != o[1]),
+    ) {
+        let mut filter = BloomFilter::<u64, XXHasher>::with_seed_offsets(items.len(), 0.01, offsets);
+        for item in &items {
+            filter.insert(item);
+        }
+
+        let dense = BloomFilter::<u64, XXHasher, DenseBits>::from_bytes(&filter.as_bytes()).unwrap();
+
+        for item in items.iter().chain(&absent) {
+            prop_assert_eq!(dense.contains(item), filter.contains(item), "item {}", item);
+        }
+        prop_assert_eq!(dense.as_bytes(), filter.as_bytes());
+    }
+
+    /// Property: Byte-key filters round-trip exactly
+    #[test]
+    fn byte_filter_round_trip(
+        keys in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..32), 1..200),
+        absent in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..32), 100),
+    ) {
+        let mut filter = ByteBloomFilter::<XXHasher>::new(keys.len(), 0.01);
+        for key in &keys {
+            filter.insert(key);
+        }
+
+        let decoded = ByteBloomFilter::<XXHasher>::from_bytes(&filter.as_bytes()).unwrap();
+
+        for key in keys.iter().chain(&absent) {
+            prop_assert_eq!(decoded.contains(key), filter.contains(key));
+        }
+    }
+}